@@ -0,0 +1,303 @@
+// Structured, audit-logged commands shared by `git issue mcp` and `git issue serve`
+
+use anyhow::{anyhow, bail, Result};
+use chrono::SecondsFormat;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::commands::{self, view_json, PlanSpec};
+use crate::logging::{append_log, edit_entry, join_items, log_cascade, reason_suffix};
+use crate::models::CloseReason;
+use crate::storage::{child_ids, load, load_all};
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct LsParams {
+    /// Any workflow state, open, closed, or all [default: open, or ls.state]
+    pub state: Option<String>,
+    /// Only issues with all of these labels
+    #[serde(default)]
+    pub label: Vec<String>,
+    /// Only issues assigned to this person (`@me` for yourself)
+    pub assignee: Option<String>,
+    /// Only issues in this milestone
+    pub milestone: Option<String>,
+    /// Only this issue and its sub-issues
+    pub root: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SearchParams {
+    /// Text to find in titles, content and comments, ignoring case
+    pub query: String,
+    /// Any workflow state, open, closed, or all [default: all]
+    pub state: Option<String>,
+    /// Only issues with all of these labels
+    #[serde(default)]
+    pub label: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct IdParams {
+    /// Issue ID, e.g. 001 or 001-002
+    pub id: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct MessageParams {
+    /// Issue ID, e.g. 001 or 001-002
+    pub id: String,
+    pub message: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct CloseParams {
+    /// Issue ID, e.g. 001 or 001-002
+    pub id: String,
+    /// Why the issue is closed
+    pub message: String,
+    /// Closed without completing [default: completed]
+    pub reason: Option<CloseReason>,
+    /// Issue this one duplicates, for `duplicate` closes
+    pub duplicate_of: Option<String>,
+    /// Also close every open descendant, deepest first
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct StatusParams {
+    /// Issue ID, e.g. 001 or 001-002
+    pub id: String,
+    /// Workflow state to move to
+    pub state: String,
+    pub message: Option<String>,
+    /// Closed without completing, for terminal states [default: completed]
+    pub reason: Option<CloseReason>,
+    /// Issue this one duplicates, for `duplicate` closes
+    pub duplicate_of: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct NoParams {}
+
+fn parse<T: DeserializeOwned>(arguments: Value) -> Result<T> {
+    serde_json::from_value(arguments).map_err(|e| anyhow!("invalid arguments: {}", e))
+}
+
+/// Run a command and log it as the CLI would, returning its structured result
+///
+/// Commands that write take the store lock first, as the CLI does; `claim` takes it itself.
+/// Only per-issue commands are offered: label, milestone and config management, migrate,
+/// import/export (files on this machine) and the text-only board, stats, agenda and report
+/// stay CLI-only.
+pub(crate) fn call(name: &str, arguments: Value) -> Result<Value> {
+    let _lock = match name {
+        "ls" | "search" | "view" | "next" | "claim" => None,
+        _ => Some(crate::storage::lock()?),
+    };
+    match name {
+        "create" => {
+            let issue = commands::create(parse(arguments)?)?;
+            append_log(&format!("CREATE id={} title={}", issue.id, issue.title))?;
+            Ok(view_json(&issue, &load_all()?)?)
+        }
+        "plan" => {
+            parse::<PlanSpec>(arguments.clone())?;
+            let args = crate::PlanArgs { file: None, json: Some(arguments.to_string()) };
+            let (parent_id, parent_title) = commands::plan(args)?;
+            append_log(&format!("PLAN parent_id={} parent_title={}", parent_id, parent_title))?;
+            Ok(json!({ "id": parent_id, "title": parent_title, "sub_issues": child_ids(&parent_id)? }))
+        }
+        "ls" => {
+            let params: LsParams = parse(arguments)?;
+            let filter = crate::IssueFilter {
+                state: params.state,
+                labels: crate::LabelFilter { label: params.label, ..Default::default() },
+                assignee: params.assignee,
+                milestone: params.milestone,
+                root: params.root,
+                ..Default::default()
+            };
+            let args = crate::LsArgs { filter, sort: None, order: None, tree: false, depth: None, format: None };
+            Ok(json!({ "issues": commands::list(args)? }))
+        }
+        "search" => {
+            let params: SearchParams = parse(arguments)?;
+            let args = crate::SearchArgs {
+                query: params.query,
+                state: params.state.unwrap_or_else(|| "all".to_string()),
+                labels: crate::LabelFilter { label: params.label, ..Default::default() },
+                format: None,
+            };
+            Ok(json!({ "issues": commands::search(args)? }))
+        }
+        "view" => {
+            let params: IdParams = parse(arguments)?;
+            let args = crate::ViewArgs { id: params.id.clone(), format: None, no_color: true, no_pager: true, raw: true, render: false };
+            commands::view(args)?;
+            Ok(view_json(&load(&params.id)?, &load_all()?)?)
+        }
+        "edit" => {
+            let args: crate::EditArgs = parse(arguments)?;
+            let issue = commands::edit(args.clone())?;
+            append_log(&edit_entry(&args, &issue))?;
+            Ok(view_json(&issue, &load_all()?)?)
+        }
+        "assign" => {
+            let args: crate::AssignArgs = parse(arguments)?;
+            let assignees = commands::assign(&args.id, &args.who, args.remove)?;
+            append_log(&format!("ASSIGN id={} assignees={}", args.id, assignees.join(",")))?;
+            Ok(view_json(&load(&args.id)?, &load_all()?)?)
+        }
+        "claim" => {
+            let args: crate::ClaimArgs = parse(arguments)?;
+            // Log lapsed leases even if the claim itself is refused
+            let mut released = commands::release_expired()?;
+            let outcome = commands::claim(args.id.as_deref(), args.who.as_deref(), args.lease.as_deref());
+            if let Ok(outcome) = &outcome {
+                released.extend(outcome.released.iter().cloned());
+            }
+            for (id, by) in released {
+                append_log(&format!("RELEASE id={} by={} expired=true", id, by))?;
+            }
+            let Some(issue) = outcome?.claimed else {
+                return Ok(json!({ "issue": null }));
+            };
+            let claim = issue.claim.as_ref().expect("claimed issue holds a claim");
+            append_log(&format!(
+                "CLAIM id={} by={} until={}",
+                issue.id,
+                claim.by,
+                claim.until.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ))?;
+            Ok(json!({ "issue": view_json(&issue, &load_all()?)? }))
+        }
+        "release" => {
+            let params: IdParams = parse(arguments)?;
+            let by = commands::release(&params.id)?;
+            append_log(&format!("RELEASE id={} by={}", params.id, by))?;
+            Ok(view_json(&load(&params.id)?, &load_all()?)?)
+        }
+        "comment" => {
+            let params: MessageParams = parse(arguments)?;
+            commands::comment(&params.id, &params.message)?;
+            append_log(&format!("COMMENT id={} msg={}", params.id, params.message))?;
+            Ok(view_json(&load(&params.id)?, &load_all()?)?)
+        }
+        "close" => {
+            let params: CloseParams = parse(arguments)?;
+            if !params.recursive {
+                return close(params);
+            }
+            if params.duplicate_of.is_some() {
+                bail!("duplicate_of cannot be combined with recursive");
+            }
+            // Asking for `recursive` is the confirmation the CLI wants from `--yes`
+            let (closed, parents) = commands::close_recursive(&params.id, &params.message, params.reason, true)?;
+            for id in &closed {
+                append_log(&format!(
+                    "CLOSE id={} msg={}{} recursive={}",
+                    id,
+                    params.message,
+                    reason_suffix(params.reason, None),
+                    params.id
+                ))?;
+            }
+            log_cascade(&params.id, &parents)?;
+            let mut value = view_json(&load(&params.id)?, &load_all()?)?;
+            value["closed"] = json!(closed);
+            value["closed_parents"] = json!(parents);
+            Ok(value)
+        }
+        "reopen" => {
+            let params: MessageParams = parse(arguments)?;
+            commands::reopen(&params.id, &params.message)?;
+            append_log(&format!("REOPEN id={} msg={}", params.id, params.message))?;
+            Ok(view_json(&load(&params.id)?, &load_all()?)?)
+        }
+        "status" => {
+            let params: StatusParams = parse(arguments)?;
+            let duplicate_of = params.duplicate_of.as_deref();
+            let message = params.message.as_deref();
+            let (state, parents) = commands::status(&params.id, &params.state, message, params.reason, duplicate_of)?;
+            append_log(&format!(
+                "STATUS id={} state={} msg={}{}",
+                params.id,
+                state,
+                message.unwrap_or_default(),
+                reason_suffix(params.reason, duplicate_of)
+            ))?;
+            log_cascade(&params.id, &parents)?;
+            let mut value = view_json(&load(&params.id)?, &load_all()?)?;
+            value["closed_parents"] = json!(parents);
+            Ok(value)
+        }
+        "prioritize" => {
+            let args: crate::PrioritizeArgs = parse(arguments)?;
+            let priority = commands::prioritize(&args.id, &args.priority)?;
+            let priority = priority.map_or("none".to_string(), |p| format!("p{}", p));
+            append_log(&format!("PRIORITIZE id={} priority={}", args.id, priority))?;
+            Ok(view_json(&load(&args.id)?, &load_all()?)?)
+        }
+        "reorder" => {
+            let args: crate::ReorderArgs = parse(arguments)?;
+            let order = commands::reorder(&args.id, args.before.as_deref(), args.after.as_deref())?;
+            append_log(&format!("REORDER id={} order={}", args.id, order.join(",")))?;
+            Ok(json!({ "order": order }))
+        }
+        "check" | "uncheck" => {
+            let args: crate::CheckArgs = parse(arguments)?;
+            let checked = name == "check";
+            let (done, total) = commands::check(&args.id, &args.items, checked)?;
+            append_log(&format!(
+                "{} id={} items={} progress={}/{}",
+                name.to_uppercase(),
+                args.id,
+                join_items(&args.items),
+                done,
+                total
+            ))?;
+            Ok(view_json(&load(&args.id)?, &load_all()?)?)
+        }
+        "estimate" => {
+            let args: crate::EstimateArgs = parse(arguments)?;
+            let estimate = commands::estimate(&args.id, &args.estimate)?;
+            let estimate = estimate.map_or("none".to_string(), |e| e.to_string());
+            append_log(&format!("ESTIMATE id={} estimate={}", args.id, estimate))?;
+            Ok(view_json(&load(&args.id)?, &load_all()?)?)
+        }
+        "track" => {
+            let args: crate::TrackArgs = parse(arguments)?;
+            let entry = commands::track(&args.id, &args.spent, &args.message, args.who.as_deref())?;
+            append_log(&format!(
+                "TRACK id={} minutes={} by={} msg={}",
+                args.id, entry.minutes, entry.by, entry.message
+            ))?;
+            Ok(view_json(&load(&args.id)?, &load_all()?)?)
+        }
+        "next" => {
+            parse::<NoParams>(arguments)?;
+            let issue = commands::next()?;
+            let issue = issue.map(|i| view_json(&i, &load_all()?)).transpose()?;
+            Ok(json!({ "issue": issue }))
+        }
+        _ => bail!("unknown command '{}'", name),
+    }
+}
+
+/// A single close, logged as `git issue close` without `--recursive`
+fn close(params: CloseParams) -> Result<Value> {
+    let duplicate_of = params.duplicate_of.as_deref();
+    let parents = commands::close(&params.id, &params.message, params.reason, duplicate_of)?;
+    append_log(&format!(
+        "CLOSE id={} msg={}{}",
+        params.id,
+        params.message,
+        reason_suffix(params.reason, duplicate_of)
+    ))?;
+    log_cascade(&params.id, &parents)?;
+    let mut value = view_json(&load(&params.id)?, &load_all()?)?;
+    value["closed_parents"] = json!(parents);
+    Ok(value)
+}
@@ -0,0 +1,187 @@
+// Kanban board layout for `git issue board`
+
+/// Columns narrower than this fall back to the plain layout
+pub const MIN_COLUMN_WIDTH: usize = 16;
+const SEPARATOR: &str = " │ ";
+/// Title lines shown on a card before it is cut off
+const TITLE_LINES: usize = 2;
+
+pub struct Card {
+    pub id: String,
+    pub title: String,
+    pub labels: Vec<String>,
+    /// Short markers after the ID, e.g. `[p1]` or `@alice`
+    pub tags: Vec<String>,
+}
+
+pub struct Column {
+    pub name: String,
+    pub cards: Vec<Card>,
+}
+
+impl Card {
+    /// Card lines fitted to a column: ID and tags, the wrapped title, then labels
+    fn lines(&self, width: usize) -> Vec<String> {
+        let mut head = self.id.clone();
+        for tag in &self.tags {
+            head.push(' ');
+            head.push_str(tag);
+        }
+        let mut lines = vec![fit(&head, width)];
+        lines.extend(wrap(&self.title, width, TITLE_LINES));
+        if !self.labels.is_empty() {
+            lines.push(fit(&self.labels.join(","), width));
+        }
+        lines
+    }
+
+    /// `ID | title - labels [tags]`, as in `ls`
+    fn plain(&self) -> String {
+        let mut line = format!("{} | {}", self.id, self.title);
+        if !self.labels.is_empty() {
+            line.push_str(&format!(" - {}", self.labels.join(",")));
+        }
+        for tag in &self.tags {
+            line.push(' ');
+            line.push_str(tag);
+        }
+        line
+    }
+}
+
+impl Column {
+    fn header(&self) -> String {
+        format!("{} ({})", self.name, self.cards.len())
+    }
+}
+
+/// Cut `text` to `width` characters, marking the cut with `…`
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Word-wrap `text` into at most `max_lines` lines of `width` characters
+fn wrap(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if current.is_empty() {
+            current = word.to_string();
+        } else if current.chars().count() + 1 + word.chars().count() <= width {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    lines.push(current);
+    if lines.len() > max_lines {
+        // The first dropped line did not fit after the last kept one, so joining them forces the `…`
+        let rest = lines.split_off(max_lines);
+        let last = lines.pop().unwrap_or_default();
+        lines.push(format!("{} {}", last, rest[0]));
+    }
+    lines.iter().map(|line| fit(line, width)).collect()
+}
+
+/// Side-by-side columns filling `width`, or `None` when the columns would be too narrow
+pub fn render(columns: &[Column], width: usize) -> Option<String> {
+    if columns.is_empty() {
+        return Some(String::new());
+    }
+    let gaps = SEPARATOR.chars().count() * (columns.len() - 1);
+    let column_width = width.saturating_sub(gaps) / columns.len();
+    if column_width < MIN_COLUMN_WIDTH {
+        return None;
+    }
+
+    let cells: Vec<Vec<String>> = columns
+        .iter()
+        .map(|column| {
+            let mut lines = vec![fit(&column.header(), column_width), "─".repeat(column_width)];
+            for (i, card) in column.cards.iter().enumerate() {
+                if i > 0 {
+                    lines.push(String::new());
+                }
+                lines.extend(card.lines(column_width));
+            }
+            lines
+        })
+        .collect();
+    let height = cells.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for row in 0..height {
+        let line: Vec<String> = cells
+            .iter()
+            .map(|lines| format!("{:<width$}", lines.get(row).map_or("", String::as_str), width = column_width))
+            .collect();
+        out.push_str(line.join(SEPARATOR).trim_end());
+        out.push('\n');
+    }
+    Some(out)
+}
+
+/// One section per column, for pipes and narrow terminals
+pub fn plain(columns: &[Column]) -> String {
+    let sections: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            let mut section = format!("== {} ==\n", column.header());
+            for card in &column.cards {
+                section.push_str(&card.plain());
+                section.push('\n');
+            }
+            section
+        })
+        .collect();
+    sections.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: &str, title: &str, labels: &[&str]) -> Card {
+        Card {
+            id: id.to_string(),
+            title: title.to_string(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_wrap_and_fit() {
+        assert_eq!(fit("abcdef", 4), "abc…");
+        assert_eq!(fit("abc", 4), "abc");
+        assert_eq!(wrap("fix the login page", 10, 2), vec!["fix the", "login page"]);
+        assert_eq!(wrap("fix the login page now", 10, 2), vec!["fix the", "login pag…"]);
+        assert_eq!(wrap("internationalization", 10, 2), vec!["internati…"]);
+    }
+
+    #[test]
+    fn test_render_columns() {
+        let columns = vec![
+            Column { name: "todo".into(), cards: vec![card("001", "Login fails on Safari", &["bug"])] },
+            Column { name: "done".into(), cards: Vec::new() },
+        ];
+        let board = render(&columns, 39).unwrap();
+        assert_eq!(
+            board,
+            "todo (1)           │ done (0)\n\
+             ────────────────── │ ──────────────────\n\
+             001                │\n\
+             Login fails on     │\n\
+             Safari             │\n\
+             bug                │\n"
+        );
+        assert!(render(&columns, 30).is_none());
+        assert_eq!(plain(&columns), "== todo (1) ==\n001 | Login fails on Safari - bug\n\n== done (0) ==\n");
+    }
+}
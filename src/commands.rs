@@ -1,230 +1,1938 @@
 // Business logic for CLI commands
 
-use crate::models::{Issue, State};
-use crate::storage::{save, load, next_root_id, next_child_id, STORAGE_DIR};
-use anyhow::{Result};
-use std::collections::HashMap;
+use crate::config::{Config, Format, Workflow};
+use crate::output::{out, outln};
+use crate::render::Renderer;
+use crate::models::{format_minutes, parent_of, parse_date_or_none, parse_duration, parse_priority, Claim, CloseReason, Issue, Progress, State, WorkEntry};
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, SubsecRound, Utc};
+use crate::storage::{save, load, load_all, child_ids, next_root_id, next_child_id, path_for};
+use anyhow::{bail, Context, Result};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
 
 pub fn create(args: crate::CreateArgs) -> Result<Issue> {
+    let config = Config::load()?;
+    let labels = args.label.clone().unwrap_or_default();
+    crate::labels::check(&labels, &config.labels)?;
+    if let Some(name) = &args.milestone {
+        crate::milestones::find(name)?;
+    }
+    check_dates(args.start, args.due)?;
+    let content = templated_content(&config, &args, &labels)?;
+    let estimate = args.estimate.as_deref().map(|e| config.estimate.unit.parse(e)).transpose()?;
+    let mut extra = BTreeMap::new();
+    set_fields(&config, &mut extra, &args.field)?;
+
     let id = if let Some(parent) = args.parent.clone() {
         load(&parent)?; // ensure parent exists
-        next_child_id(&parent)?
+        next_child_id(&parent, &config.id)?
     } else {
-        next_root_id()?
+        next_root_id(&config.id)?
     };
 
-    let labels = args.label.unwrap_or_default();
-
     let issue = Issue {
         id: id.clone(),
         title: args.title,
-        content: args.content,
+        content,
         labels,
-        state: State::Open,
-        comments: Vec::new(),
+        state: config.workflow.initial_state(),
+        auto_close: args.auto_close,
+        milestone: args.milestone,
+        due: args.due,
+        start: args.start,
+        estimate,
+        extra,
+        ..Default::default()
     };
 
     save(&issue)?;
 
     // Print summary
-    if !issue.labels.is_empty() {
-        println!("{} | {} - {}", issue.id, issue.title, issue.labels.join(","));
-    } else {
-        println!("{} | {}", issue.id, issue.title);
-    }
+    outln!("{}", headline(&issue));
 
     Ok(issue.clone())
 }
 
-pub fn list(args: crate::LsArgs) -> Result<()> {
+/// Body for a new issue, filled from or checked against its template
+fn templated_content(config: &Config, args: &crate::CreateArgs, labels: &[String]) -> Result<String> {
+    let Some(template) = crate::templates::select(args.template.as_deref(), labels, &config.labels)? else {
+        if args.content.trim().is_empty() {
+            bail!("content is required unless a template supplies it");
+        }
+        return Ok(args.content.clone());
+    };
+    let author = config.author.name().unwrap_or_default();
+    let date = Local::now().date_naive().to_string();
+    let joined = labels.join(",");
+    template.apply(
+        &args.content,
+        &[
+            ("title", args.title.as_str()),
+            ("author", &author),
+            ("date", &date),
+            ("labels", &joined),
+            ("parent", args.parent.as_deref().unwrap_or_default()),
+        ],
+    )
+}
+
+/// Set or clear custom fields from `KEY=VALUE` assignments
+fn set_fields(
+    config: &Config,
+    extra: &mut BTreeMap<String, serde_yaml::Value>,
+    assignments: &[String],
+) -> Result<()> {
+    for assignment in assignments {
+        match config.parse_field(assignment)? {
+            (key, Some(value)) => extra.insert(key, value),
+            (key, None) => extra.remove(&key),
+        };
+    }
+    Ok(())
+}
+
+/// Custom field value as shown to users
+pub(crate) fn field_text(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+/// Change an issue's title, content, dates, labels or custom fields
+pub fn edit(args: crate::EditArgs) -> Result<Issue> {
+    let config = Config::load()?;
+    let mut issue = load(&args.id)?;
+    if args.title.is_none()
+        && args.content.is_none()
+        && args.due.is_none()
+        && args.start.is_none()
+        && args.field.is_empty()
+        && args.add_label.is_empty()
+        && args.remove_label.is_empty()
+    {
+        bail!("nothing to edit (use --title, --content, --due, --start, --field, --add-label or --remove-label)");
+    }
+    if let Some(title) = args.title {
+        issue.title = title;
+    }
+    if let Some(content) = args.content {
+        issue.content = content;
+    }
+    if let Some(due) = &args.due {
+        issue.due = parse_date_or_none(due)?;
+    }
+    if let Some(start) = &args.start {
+        issue.start = parse_date_or_none(start)?;
+    }
+    check_dates(issue.start, issue.due)?;
+    set_fields(&config, &mut issue.extra, &args.field)?;
+    if !args.add_label.is_empty() || !args.remove_label.is_empty() {
+        issue.labels.retain(|l| !args.remove_label.iter().any(|r| config.labels.matches(l, r)));
+        for label in &args.add_label {
+            if !issue.labels.iter().any(|l| config.labels.matches(l, label)) {
+                issue.labels.push(label.clone());
+            }
+        }
+        crate::labels::check(&issue.labels, &config.labels)?;
+    }
+    save(&issue)?;
+    outln!("{}", summary(&issue, &config.workflow));
+    Ok(issue)
+}
+
+/// Reject a planned start that falls after the due date
+pub(crate) fn check_dates(start: Option<NaiveDate>, due: Option<NaiveDate>) -> Result<()> {
+    if let (Some(start), Some(due)) = (start, due) {
+        if start > due {
+            bail!("start date {} is after due date {}", start, due);
+        }
+    }
+    Ok(())
+}
+
+/// Issues passing an `ls`-style filter, in id order, alongside every loaded issue
+fn select(config: &Config, filter: &crate::IssueFilter) -> Result<(Vec<Issue>, Vec<Issue>)> {
+    let state = filter.state.clone().unwrap_or(config.ls.state.clone());
+    let mut labels = filter.labels.clone();
+    if labels.is_empty() {
+        labels.label.extend(config.ls.label.clone());
+    }
+    let fields = filter
+        .field
+        .iter()
+        .map(|f| match config.parse_field(f)? {
+            (key, Some(value)) => Ok((key, value)),
+            (key, None) => bail!("--field {}= needs a value", key),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let assignee = filter.assignee.as_deref().map(|a| config.author.resolve(a)).transpose()?;
+    let due_within = filter.due_within.as_deref().map(parse_duration).transpose()?.map(|d| d.num_days());
+    let today = Local::now().date_naive();
+    if let Some(root) = &filter.root {
+        load(root)?;
+    }
+
+    let all = load_all()?;
+    let by_id: HashMap<&str, &Issue> = all.iter().map(|i| (i.id.as_str(), i)).collect();
+    let mut matched: Vec<Issue> = Vec::new();
+    for issue in all.iter() {
+        if !config.workflow.filter_matches(&state, &issue.state)? {
+            continue;
+        }
+        if let Some(root) = &filter.root {
+            if issue.id != *root && !issue.id.starts_with(&format!("{}-", root)) {
+                continue;
+            }
+        }
+        if !crate::labels::filter_matches(&labels, &issue.labels, &config.labels) {
+            continue;
+        }
+        if assignee.as_ref().is_some_and(|a| !issue.is_assigned_to(a))
+            || (filter.unassigned && !issue.assignees.is_empty())
+        {
+            continue;
+        }
+        if !fields.iter().all(|(key, value)| issue.extra.get(key) == Some(value)) {
+            continue;
+        }
+        if filter.reference.is_some() && issue.reference != filter.reference {
+            continue;
+        }
+        if filter.milestone.is_some()
+            && crate::milestones::effective(issue, &by_id) != filter.milestone.as_deref()
+        {
+            continue;
+        }
+        // Date filters only consider open issues with a due date
+        let days_left = issue
+            .days_until_due(today)
+            .filter(|_| !config.workflow.is_terminal(&issue.state));
+        if (filter.overdue && days_left.is_none_or(|d| d >= 0))
+            || due_within.is_some_and(|n| days_left.is_none_or(|d| d > n))
+        {
+            continue;
+        }
+        matched.push(issue.clone());
+    }
+    Ok((matched, all))
+}
+
+/// Print matching issues, returning them in listing order
+pub fn list(args: crate::LsArgs) -> Result<Vec<Issue>> {
+    let config = Config::load()?;
+    let sort = args.sort.unwrap_or(config.ls.sort.clone());
+    let order = args.order.unwrap_or(config.ls.order.clone());
+    let format = args.format.unwrap_or(config.output.format);
+    let compare = order_by(&sort).map(boxed).or_else(|e| match config.fields.get(&sort) {
+        Some(def) => Ok(field_order(def.clone(), sort.clone())),
+        None => Err(e),
+    })?;
+    if args.tree && format == Format::Json {
+        bail!("--tree only supports text output");
+    }
+    if args.depth == Some(0) {
+        bail!("--depth must be at least 1");
+    }
+
     let mut roots: Vec<Issue> = Vec::new();
     let mut children_map: HashMap<String, Vec<Issue>> = HashMap::new();
+    let (matched, all) = select(&config, &args.filter)?;
 
-    for entry in fs::read_dir(STORAGE_DIR)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() {
-            let name = entry.file_name().into_string().unwrap();
-            if let Some(id) = name.strip_suffix(".yaml") {
-                let issue = load(id)?;
-                if args.state != "all" && issue.state.to_string() != args.state {
-                    continue;
-                }
-                if let Some(ref filter) = args.label {
-                    let filter = filter.trim().to_lowercase();
-                    if !issue.labels.iter().any(|l| l.trim().to_lowercase() == filter) {
-                        continue;
-                    }
-                }
-                roots.push(issue);
-            }
-        } else if path.is_dir() {
-            let parent = entry.file_name().into_string().unwrap();
-            for child_entry in fs::read_dir(&path)? {
-                let child_entry = child_entry?;
-                let fname = child_entry.file_name().into_string().unwrap();
-                if let Some(child_id) = fname.strip_suffix(".yaml") {
-                    let issue = load(child_id)?;
-                    if args.state != "all" && issue.state.to_string() != args.state {
-                        continue;
-                    }
-                    if let Some(ref filter) = args.label {
-                        let filter = filter.trim().to_lowercase();
-                        if !issue.labels.iter().any(|l| l.trim().to_lowercase() == filter) {
-                            continue;
-                        }
-                    }
-                    children_map.entry(parent.clone()).or_default().push(issue);
-                }
+    if args.tree {
+        let tree = Tree::new(&all, &matched, args.filter.root.as_deref(), &config.workflow, args.depth);
+        let mut tops = tree.tops();
+        tops.sort_by(|a, b| compare(a, b));
+        if order == "desc" {
+            tops.reverse();
+        }
+        for issue in tops {
+            tree.render(issue, "", None, 1, &compare);
+        }
+        return Ok(matched.clone());
+    }
+
+    // Sub-issues nest under their parent when it is listed too, otherwise they stand alone
+    let matched_ids: HashSet<String> = matched.iter().map(|i| i.id.clone()).collect();
+    for issue in matched {
+        match issue.parent_id() {
+            Some(parent) if matched_ids.contains(parent) => {
+                children_map.entry(parent.to_string()).or_default().push(issue)
             }
+            _ => roots.push(issue),
         }
     }
 
-    roots.sort_by_key(|iss| iss.id.clone());
-    if args.order == "desc" {
+    roots.sort_by(&compare);
+    if order == "desc" {
         roots.reverse();
     }
 
+    let mut listed: Vec<Issue> = Vec::new();
     for root in roots {
-        let base = if !root.labels.is_empty() {
-            format!("{} | {} - {}", root.id, root.title, root.labels.join(","))
-        } else {
-            format!("{} | {}", root.id, root.title)
-        };
-        if root.state == State::Closed {
-            println!("{} [closed]", base);
-        } else {
-            println!("{}", base);
-        }
-        if let Some(mut children) = children_map.remove(&root.id) {
-            children.sort_by_key(|c| c.id.clone());
-            for child in children {
-                let base = if !child.labels.is_empty() {
-                    format!(
-                        "{} | {} - {}",
-                        child.id,
-                        child.title,
-                        child.labels.join(",")
-                    )
-                } else {
-                    format!("{} | {}", child.id, child.title)
-                };
-                if child.state == State::Closed {
-                    println!("{} [closed]", base);
-                } else {
-                    println!("{}", base);
+        let children = children_map.remove(&root.id);
+        listed.push(root);
+        if let Some(mut children) = children {
+            children.sort_by(&compare);
+            listed.extend(children);
+        }
+    }
+
+    if format == Format::Json {
+        outln!("{}", serde_json::to_string_pretty(&listed)?);
+        return Ok(listed);
+    }
+    for issue in &listed {
+        outln!("{}", summary(issue, &config.workflow));
+    }
+
+    Ok(listed)
+}
+
+/// Matched issues arranged by parent, with their ancestors filled in so every branch is rooted
+struct Tree<'a> {
+    all: &'a [Issue],
+    shown: Vec<&'a Issue>,
+    root: Option<&'a str>,
+    workflow: &'a Workflow,
+    depth: Option<usize>,
+}
+
+impl<'a> Tree<'a> {
+    fn new(
+        all: &'a [Issue],
+        matched: &[Issue],
+        root: Option<&'a str>,
+        workflow: &'a Workflow,
+        depth: Option<usize>,
+    ) -> Tree<'a> {
+        let by_id: HashMap<&str, &Issue> = all.iter().map(|i| (i.id.as_str(), i)).collect();
+        let mut ids: HashSet<&str> = HashSet::new();
+        for issue in matched {
+            let mut current = by_id.get(issue.id.as_str()).copied();
+            while let Some(i) = current {
+                if !ids.insert(i.id.as_str()) || Some(i.id.as_str()) == root {
+                    break;
                 }
+                current = i.parent_id().and_then(|p| by_id.get(p).copied());
             }
         }
+        let shown = all.iter().filter(|i| ids.contains(i.id.as_str())).collect();
+        Tree { all, shown, root, workflow, depth }
     }
 
-    Ok(())
+    /// Issues whose parent is not part of the tree
+    fn tops(&self) -> Vec<&'a Issue> {
+        let shown = |id: &str| self.shown.iter().any(|i| i.id == id);
+        self.shown.iter().copied().filter(|i| !i.parent_id().is_some_and(shown)).collect()
+    }
+
+    fn children(&self, id: &str) -> Vec<&'a Issue> {
+        self.shown.iter().copied().filter(|i| i.parent_id() == Some(id)).collect()
+    }
+
+    /// Print `issue` and, unless it is closed or at the depth limit, its subtree
+    ///
+    /// A closed issue picked with `--root` is still expanded.
+    ///
+    /// `last` is `None` for top-level issues, otherwise whether `issue` is its parent's last child.
+    fn render(&self, issue: &Issue, prefix: &str, last: Option<bool>, level: usize, compare: &Comparator) {
+        let (connector, indent) = match last {
+            None => ("", ""),
+            Some(false) => ("├── ", "│   "),
+            Some(true) => ("└── ", "    "),
+        };
+        let mut line = format!("{}{}{}", prefix, connector, summary(issue, self.workflow));
+        let below: Vec<&Issue> = descendants(self.all, &issue.id).collect();
+        if !below.is_empty() {
+            let progress = Progress::tally(below, |i| self.workflow.is_terminal(&i.state));
+            line.push_str(&format!(" ({}/{} sub-issues)", progress.done(), progress.total()));
+        }
+        let mut children = self.children(&issue.id);
+        let collapsed = (self.workflow.is_terminal(&issue.state) && self.root != Some(issue.id.as_str()))
+            || self.depth.is_some_and(|d| level >= d);
+        if collapsed && !children.is_empty() {
+            let hidden = self.shown.iter().filter(|i| i.id.starts_with(&format!("{}-", issue.id))).count();
+            line.push_str(&format!(" (+{} hidden)", hidden));
+            children.clear();
+        }
+        outln!("{}", line);
+        children.sort_by(|a, b| compare(a, b));
+        let prefix = format!("{}{}", prefix, indent);
+        let count = children.len();
+        for (i, child) in children.into_iter().enumerate() {
+            self.render(child, &prefix, Some(i + 1 == count), level + 1, compare);
+        }
+    }
 }
 
-pub fn view(id: &str) -> Result<()> {
+pub fn view(args: crate::ViewArgs) -> Result<()> {
+    use std::io::IsTerminal;
+
+    let id = args.id.as_str();
+    let config = Config::load()?;
+    let format = args.format.unwrap_or(config.output.format);
     let issue = load(id)?;
-    if !issue.labels.is_empty() {
-        println!("{} | {} - {}", issue.id, issue.title, issue.labels.join(","));
-    } else {
-        println!("{} | {}", issue.id, issue.title);
-    }
-    println!("\n{}\n", issue.content);
-
-    // Show any children under .issues/{id}/
-    let dir = PathBuf::from(STORAGE_DIR).join(id);
-    if dir.exists() {
-        let mut children_ids: Vec<String> = fs::read_dir(dir)?
-            .filter_map(|e| e.ok())
-            .filter_map(|e| {
-                let fname = e.file_name().into_string().ok()?;
-                fname.strip_suffix(".yaml").map(String::from)
-            })
-            .collect();
-        children_ids.sort();
+    let issues = load_all()?;
+
+    if format == Format::Json {
+        outln!("{}", serde_json::to_string_pretty(&view_json(&issue, &issues)?)?);
+        return Ok(());
+    }
+
+    // Scripts and pipes get the stored text unless they ask for rendering
+    let interactive = std::io::stdout().is_terminal() && !crate::output::is_captured();
+    let renderer = Renderer::detect(args.no_color);
+    let rendered = args.render || (interactive && !args.raw);
+    let lines = view_lines(&issue, &issues, &config, rendered.then_some(&renderer));
+    crate::render::page(&lines, !args.no_pager)
+}
+
+/// An issue as `view --format json` shows it: its fields, sub-issue IDs and rolled-up effort
+pub fn view_json(issue: &Issue, issues: &[Issue]) -> Result<serde_json::Value> {
+    let (estimate, spent) = rollup(issue, issues);
+    let mut value = serde_json::to_value(issue)?;
+    value["children"] = serde_json::json!(child_ids(&issue.id)?);
+    value["rollup"] = serde_json::json!({ "estimate": estimate, "spent_minutes": spent });
+    Ok(value)
+}
+
+/// Text of `view` for one issue, rendered unless `renderer` is `None`; `issues` supplies sub-issues for the rollup
+pub fn view_lines(issue: &Issue, issues: &[Issue], config: &Config, renderer: Option<&Renderer>) -> Vec<String> {
+    let children_ids: Vec<&str> = issues
+        .iter()
+        .filter(|i| i.parent_id() == Some(issue.id.as_str()))
+        .map(|i| i.id.as_str())
+        .collect();
+    let (estimate, spent) = rollup(issue, issues);
+
+    let summary = summary(issue, &config.workflow);
+    let mut lines = vec![renderer.map_or(summary.clone(), |r| r.title(&summary))];
+    if let Some(claim) = issue.active_claim(Utc::now()) {
+        lines.push(format!("claimed by {} until {}", claim.by, claim.until.to_rfc3339_opts(SecondsFormat::Secs, true)));
+    }
+    if let Some(start) = issue.start {
+        lines.push(format!("starts {}", start));
+    }
+    if let Some(reference) = &issue.reference {
+        lines.push(format!("imported from {}", reference));
+    }
+    for (key, value) in &issue.extra {
+        lines.push(format!("{}: {}", key, field_text(value)));
+    }
+    if estimate > 0.0 || spent > 0 {
+        let unit = config.estimate.unit;
+        let mut line = format!(
+            "estimate {}, spent {}",
+            issue.estimate.map_or("-".to_string(), |e| unit.format(e)),
+            format_minutes(issue.spent_minutes())
+        );
         if !children_ids.is_empty() {
-            println!("@ref{{{}}}", children_ids.join(", "));
+            line.push_str(&format!(
+                " (with sub-issues: estimate {}, spent {})",
+                unit.format(estimate),
+                format_minutes(spent)
+            ));
+        }
+        lines.push(line);
+    }
+    if let Some(r) = renderer {
+        for line in &mut lines[1..] {
+            *line = r.dim(line);
+        }
+    }
+    lines.push(String::new());
+    match renderer {
+        Some(r) => lines.extend(r.markdown(&issue.content)),
+        None => lines.extend(issue.content.lines().map(String::from)),
+    }
+    lines.push(String::new());
+    if !children_ids.is_empty() {
+        lines.push(format!("@ref{{{}}}", children_ids.join(", ")));
+    }
+    for (n, entry) in issue.comments.iter().enumerate() {
+        match renderer {
+            Some(r) => lines.extend(r.comment(entry, issue.comment_time(n))),
+            None => lines.push(entry.clone()),
         }
     }
+    lines
+}
 
-    for comment in issue.comments {
-        println!("{}", comment);
+/// Estimate and minutes spent, summed over an issue and all of its descendants
+fn rollup(issue: &Issue, issues: &[Issue]) -> (f64, i64) {
+    descendants(issues, &issue.id)
+        .chain(std::iter::once(issue))
+        .fold((0.0, 0), |(estimate, spent), i| {
+            (estimate + i.estimate.unwrap_or(0.0), spent + i.spent_minutes())
+        })
+}
+
+/// Bracketed state and close reason, e.g. `[closed: wontfix]`; omitted for the initial state
+fn state_tag(issue: &Issue, workflow: &Workflow) -> Option<String> {
+    match (issue.close_reason, &issue.duplicate_of) {
+        (Some(CloseReason::Duplicate), Some(of)) => {
+            Some(format!("[{}: duplicate of {}]", issue.state, of))
+        }
+        (Some(reason), _) => Some(format!("[{}: {}]", issue.state, reason)),
+        (None, _) if issue.state == workflow.initial_state() => None,
+        (None, _) => Some(format!("[{}]", issue.state)),
+    }
+}
+
+/// Headline followed by priority, assignee, due, checklist and state tags, e.g. `001 | title - bug [p1] [closed]`
+fn summary(issue: &Issue, workflow: &Workflow) -> String {
+    let mut line = headline(issue);
+    if let Some(priority) = issue.priority {
+        line.push_str(&format!(" [p{}]", priority));
+    }
+    if !issue.assignees.is_empty() {
+        let names: Vec<String> = issue.assignees.iter().map(|a| format!("@{}", a)).collect();
+        line.push_str(&format!(" [{}]", names.join(",")));
+    }
+    if let Some(due) = issue.due {
+        line.push_str(&format!(" [due {}]", due));
+    }
+    let checklist = issue.checklist();
+    if !checklist.is_empty() {
+        let done = checklist.iter().filter(|i| i.checked).count();
+        line.push_str(&format!(" [{}/{}]", done, checklist.len()));
+    }
+    if let Some(tag) = state_tag(issue, workflow) {
+        line.push(' ');
+        line.push_str(&tag);
+    }
+    line
+}
+
+type Comparator = Box<dyn Fn(&Issue, &Issue) -> Ordering>;
+
+fn boxed(compare: fn(&Issue, &Issue) -> Ordering) -> Comparator {
+    Box::new(compare)
+}
+
+/// Comparator for a custom field: issues with the field set first, then by ID
+fn field_order(def: crate::config::FieldDef, name: String) -> Comparator {
+    Box::new(move |a, b| {
+        match (a.extra.get(&name), b.extra.get(&name)) {
+            (Some(x), Some(y)) => def.compare(x, y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+        .then_with(|| a.id.cmp(&b.id))
+    })
+}
+
+/// Comparator for an `ls --sort` key, falling back to ID order
+fn order_by(sort: &str) -> Result<fn(&Issue, &Issue) -> Ordering> {
+    match sort {
+        "id" => Ok(|a, b| a.id.cmp(&b.id)),
+        "priority" => Ok(|a, b| a.priority_key().cmp(&b.priority_key()).then_with(|| a.id.cmp(&b.id))),
+        "rank" => Ok(|a, b| a.rank_key().cmp(&b.rank_key()).then_with(|| a.id.cmp(&b.id))),
+        other => bail!("unknown sort key '{}' (expected id, priority, rank or a custom field)", other),
+    }
+}
+
+/// One-line summary: `ID | title - labels`
+fn headline(issue: &Issue) -> String {
+    if !issue.labels.is_empty() {
+        format!("{} | {} - {}", issue.id, issue.title, issue.labels.join(","))
+    } else {
+        format!("{} | {}", issue.id, issue.title)
     }
-    Ok(())
 }
 
 pub fn append_comment(id: &str, entry: &str) -> Result<()> {
     let mut issue = load(id)?;
-    issue.comments.push(entry.to_string());
+    issue.add_comment(entry, Utc::now().trunc_subsecs(0));
     save(&issue)
 }
 
 pub fn comment(id: &str, message: &str) -> Result<()> {
     let entry = format!("+++ {}", message);
     append_comment(id, &entry)?;
-    println!("{} | {}", id, entry);
+    outln!("{} | {}", id, entry);
     Ok(())
 }
 
-pub fn close(id: &str, message: &str) -> Result<()> {
-    let child_dir = PathBuf::from(STORAGE_DIR).join(id);
-    if child_dir.exists() {
-        for entry in fs::read_dir(child_dir)? {
-            let entry = entry?;
-            let fname = entry.file_name().into_string().unwrap();
-            if let Some(child_id) = fname.strip_suffix(".yaml") {
-                let child = load(child_id)?;
-                if child.state == State::Open {
-                    eprintln!("error: child issues are still pending");
-                    std::process::exit(1);
-                }
-            }
+pub fn close(
+    id: &str,
+    message: &str,
+    reason: Option<CloseReason>,
+    duplicate_of: Option<&str>,
+) -> Result<Vec<String>> {
+    let workflow = Config::load()?.workflow;
+    let (reason, duplicate_of) = resolve_reason(id, reason, duplicate_of)?;
+    let entry = format!(">>> {}", message);
+    transition(id, &workflow.close_state(), &entry, &workflow, reason, duplicate_of)?;
+    outln!("{} | >>> {}", id, message);
+    close_parents(id, &workflow)
+}
+
+/// Close an issue together with all of its open descendants, deepest first
+///
+/// Prints the affected issues and changes nothing unless `yes` confirms. Returns
+/// the closed IDs followed by any parents closed by the cascade.
+pub fn close_recursive(
+    id: &str,
+    message: &str,
+    reason: Option<CloseReason>,
+    yes: bool,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let workflow = Config::load()?.workflow;
+    if reason == Some(CloseReason::Duplicate) {
+        bail!("duplicate closes cannot be recursive");
+    }
+    let root = load(id)?;
+    let issues = load_all()?;
+    let mut affected: Vec<&Issue> = descendants(&issues, id)
+        .chain(std::iter::once(&root))
+        .filter(|i| !workflow.is_terminal(&i.state))
+        .collect();
+    affected.sort_by(|a, b| b.id.cmp(&a.id));
+    if affected.is_empty() {
+        bail!("issue is already closed");
+    }
+
+    // Check every transition up front so a refusal leaves nothing half-closed
+    let target = workflow.close_state();
+    for issue in &affected {
+        if !workflow.allows(&issue.state, &target) {
+            bail!("transition from {} to {} is not allowed for {}", issue.state, target, issue.id);
+        }
+        let unchecked = unchecked_items(issue, &workflow, reason);
+        if unchecked > 0 {
+            bail!("{} has {} unchecked checklist item(s)", issue.id, unchecked);
         }
     }
+
+    let ids: Vec<String> = affected.iter().map(|i| i.id.clone()).collect();
+    outln!("closing {} issue(s): {}", ids.len(), ids.join(", "));
+    if !yes {
+        bail!("re-run with --yes to close these issues");
+    }
     let entry = format!(">>> {}", message);
-    append_comment(id, &entry)?;
+    for issue_id in &ids {
+        transition(issue_id, &target, &entry, &workflow, reason, None)?;
+        outln!("{} | >>> {}", issue_id, message);
+    }
+    let parents = close_parents(id, &workflow)?;
+    Ok((ids, parents))
+}
+
+pub fn reopen(id: &str, message: &str) -> Result<()> {
+    let workflow = Config::load()?.workflow;
+    if !workflow.is_terminal(&load(id)?.state) {
+        bail!("issue is not closed");
+    }
+    let entry = format!("<<< {}", message);
+    transition(id, &workflow.reopen_state(), &entry, &workflow, None, None)?;
+    outln!("{} | <<< {}", id, message);
+    Ok(())
+}
+
+/// Move an issue to any configured workflow state
+pub fn status(
+    id: &str,
+    state: &str,
+    message: Option<&str>,
+    reason: Option<CloseReason>,
+    duplicate_of: Option<&str>,
+) -> Result<(State, Vec<String>)> {
+    let workflow = Config::load()?.workflow;
+    let target = workflow.state(state)?;
+    let (reason, duplicate_of) = resolve_reason(id, reason, duplicate_of)?;
+    if reason.is_some() && !workflow.is_terminal(&target) {
+        bail!("a close reason needs a terminal state, and {} is not", target);
+    }
+    let from = load(id)?.state;
+    let marker = if workflow.is_terminal(&target) {
+        ">>>"
+    } else if workflow.is_terminal(&from) {
+        "<<<"
+    } else {
+        "~~~"
+    };
+    let entry = match message {
+        Some(message) => format!("{} {}: {}", marker, target, message),
+        None => format!("{} {}", marker, target),
+    };
+    transition(id, &target, &entry, &workflow, reason, duplicate_of)?;
+    outln!("{} | {} -> {}", id, from, target);
+    let parents = if workflow.is_terminal(&target) {
+        close_parents(id, &workflow)?
+    } else {
+        Vec::new()
+    };
+    Ok((target, parents))
+}
+
+/// After `id` closes, close each ancestor that opted into auto-close and has no open sub-issues left
+///
+/// Walks up the tree until an ancestor stays open, returning the IDs it closed.
+fn close_parents(id: &str, workflow: &Workflow) -> Result<Vec<String>> {
+    let target = workflow.close_state();
+    let mut closed = Vec::new();
+    let mut child = id.to_string();
+    while let Some(parent_id) = parent_of(&child).map(str::to_string) {
+        let parent = load(&parent_id)?;
+        let issues = load_all()?;
+        if !parent.auto_close.unwrap_or(workflow.auto_close_parents)
+            || workflow.is_terminal(&parent.state)
+            || !workflow.allows(&parent.state, &target)
+            || unchecked_items(&parent, workflow, None) > 0
+            || descendants(&issues, &parent_id).any(|i| !workflow.is_terminal(&i.state))
+        {
+            break;
+        }
+        let entry = format!(">>> all sub-issues closed (last: {})", child);
+        transition(&parent_id, &target, &entry, workflow, None, None)?;
+        outln!("{} | {}", parent_id, entry);
+        closed.push(parent_id.clone());
+        child = parent_id;
+    }
+    Ok(closed)
+}
+
+/// Validate a close reason; `--duplicate-of` alone implies `duplicate`
+fn resolve_reason(
+    id: &str,
+    reason: Option<CloseReason>,
+    duplicate_of: Option<&str>,
+) -> Result<(Option<CloseReason>, Option<String>)> {
+    let reason = reason.or(duplicate_of.map(|_| CloseReason::Duplicate));
+    match (reason, duplicate_of) {
+        (Some(CloseReason::Duplicate), Some(of)) => {
+            if of == id {
+                bail!("an issue cannot duplicate itself");
+            }
+            load(of)?; // ensure the original exists
+            Ok((reason, Some(of.to_string())))
+        }
+        (Some(CloseReason::Duplicate), None) => bail!("--duplicate-of is required for duplicate closes"),
+        (_, Some(_)) => bail!("--duplicate-of only applies to duplicate closes"),
+        (reason, None) => Ok((reason, None)),
+    }
+}
+
+/// Unticked checklist items that block a completed close under `workflow.require_checklist`
+fn unchecked_items(issue: &Issue, workflow: &Workflow, reason: Option<CloseReason>) -> usize {
+    if !workflow.require_checklist
+        || matches!(reason, Some(CloseReason::Wontfix | CloseReason::Duplicate))
+    {
+        return 0;
+    }
+    issue.checklist().iter().filter(|i| !i.checked).count()
+}
+
+/// Apply a state change, enforcing allowed transitions and the parent/child rules
+fn transition(
+    id: &str,
+    target: &State,
+    entry: &str,
+    workflow: &Workflow,
+    reason: Option<CloseReason>,
+    duplicate_of: Option<String>,
+) -> Result<()> {
+    let mut issue = load(id)?;
+    if issue.state == *target {
+        bail!("issue is already {}", target);
+    }
+    if !workflow.allows(&issue.state, target) {
+        bail!("transition from {} to {} is not allowed", issue.state, target);
+    }
+    if workflow.is_terminal(target) {
+        let unchecked = unchecked_items(&issue, workflow, reason);
+        if unchecked > 0 {
+            bail!("{} checklist item(s) still unchecked", unchecked);
+        }
+        for child_id in child_ids(id)? {
+            if !workflow.is_terminal(&load(&child_id)?.state) {
+                bail!("child issues are still pending");
+            }
+        }
+    } else if workflow.is_terminal(&issue.state) {
+        if let Some(parent) = issue.parent_id() {
+            if workflow.is_terminal(&load(parent)?.state) {
+                bail!("parent issue closed");
+            }
+        }
+    }
+    issue.state = target.clone();
+    issue.close_reason = reason;
+    issue.duplicate_of = duplicate_of;
+    issue.add_comment(entry, Utc::now().trunc_subsecs(0));
+    save(&issue)
+}
+
+/// Issues ready to work on, best first: open and with no open sub-issues
+///
+/// Candidates are ordered by priority, then by rank along their path through
+/// the tree, then by ID.
+fn ready_issues<'a>(issues: &'a [Issue], workflow: &Workflow) -> Vec<&'a Issue> {
+    let is_open = |issue: &Issue| !workflow.is_terminal(&issue.state);
+    let by_id: HashMap<&str, &Issue> = issues.iter().map(|i| (i.id.as_str(), i)).collect();
+    let tree_position = |issue: &Issue| -> Vec<(u32, String)> {
+        let mut path = Vec::new();
+        let mut current = Some(issue);
+        while let Some(i) = current {
+            path.push((i.rank_key(), i.id.clone()));
+            current = i.parent_id().and_then(|p| by_id.get(p).copied());
+        }
+        path.reverse();
+        path
+    };
+    let mut ready: Vec<&Issue> = issues
+        .iter()
+        .filter(|i| is_open(i) && !descendants(issues, &i.id).any(is_open))
+        .collect();
+    ready.sort_by_cached_key(|i| (i.priority_key(), tree_position(i)));
+    ready
+}
+
+/// Show the next ready issue
+pub fn next() -> Result<Option<Issue>> {
+    let workflow = Config::load()?.workflow;
+    let issues = load_all()?;
+    let is_open = |issue: &Issue| !workflow.is_terminal(&issue.state);
+    let Some(issue) = ready_issues(&issues, &workflow).into_iter().next() else {
+        outln!("no open issues");
+        return Ok(None);
+    };
+
+    view(crate::ViewArgs { id: issue.id.clone(), format: None, no_color: false, no_pager: true, raw: true, render: false })?;
+    if let Some((root, _)) = issue.id.split_once('-') {
+        let progress = Progress::tally(descendants(&issues, root), |i| !is_open(i));
+        outln!("progress: {}/{} ({})", progress.done(), progress.total(), root);
+    }
+    Ok(Some(issue.clone()))
+}
+
+/// Print open issues that have a due date, grouped by how soon they are due
+pub fn agenda() -> Result<()> {
+    let workflow = Config::load()?.workflow;
+    let today = Local::now().date_naive();
+    let mut issues: Vec<Issue> = load_all()?
+        .into_iter()
+        .filter(|i| i.due.is_some() && !workflow.is_terminal(&i.state))
+        .collect();
+    issues.sort_by(|a, b| a.due.cmp(&b.due).then_with(|| a.id.cmp(&b.id)));
+    if issues.is_empty() {
+        outln!("nothing due");
+        return Ok(());
+    }
+
+    type Bucket = (&'static str, fn(i64) -> bool);
+    let buckets: [Bucket; 4] = [
+        ("overdue", |days| days < 0),
+        ("today", |days| days == 0),
+        ("next 7 days", |days| (1..=7).contains(&days)),
+        ("later", |days| days > 7),
+    ];
+    let mut first = true;
+    for (name, holds) in buckets {
+        let bucket: Vec<&Issue> = issues
+            .iter()
+            .filter(|i| i.days_until_due(today).is_some_and(holds))
+            .collect();
+        if bucket.is_empty() {
+            continue;
+        }
+        if !first {
+            outln!();
+        }
+        first = false;
+        outln!("{} ({})", name, bucket.len());
+        for issue in bucket {
+            outln!("  {}", summary(issue, &workflow));
+        }
+    }
+    Ok(())
+}
+
+/// Completion statistics for the whole store, or for the sub-issues of one issue
+pub fn stats(id: Option<&str>) -> Result<Progress> {
+    let workflow = Config::load()?.workflow;
+    let issues = load_all()?;
+    let scope: Vec<&Issue> = match id {
+        Some(id) => {
+            load(id)?;
+            descendants(&issues, id).collect()
+        }
+        None => issues.iter().collect(),
+    };
+    let progress = Progress::tally(scope, |i| workflow.is_terminal(&i.state));
+    outln!("open: {}", progress.open);
+    outln!("completed: {}", progress.completed);
+    outln!("wontfix: {}", progress.wontfix);
+    outln!("duplicate: {}", progress.duplicate);
+    let percent = match progress.total() {
+        0 => 100,
+        total => progress.done() * 100 / total,
+    };
+    outln!("progress: {}/{} ({}%)", progress.done(), progress.total(), percent);
+    Ok(progress)
+}
+
+/// Issues nested anywhere below `id`
+fn descendants<'a>(issues: &'a [Issue], id: &str) -> impl Iterator<Item = &'a Issue> {
+    let prefix = format!("{}-", id);
+    issues.iter().filter(move |i| i.id.starts_with(&prefix))
+}
+
+/// Set or clear an issue's priority: `p0`..`p3`, a number, or `none`
+pub fn prioritize(id: &str, priority: &str) -> Result<Option<u8>> {
+    let mut issue = load(id)?;
+    issue.priority = parse_priority(priority)?;
+    save(&issue)?;
+    match issue.priority {
+        Some(p) => outln!("{} | priority p{}", id, p),
+        None => outln!("{} | priority cleared", id),
+    }
+    Ok(issue.priority)
+}
+
+/// Move an issue directly before or after a sibling, renumbering sibling ranks
+pub fn reorder(id: &str, before: Option<&str>, after: Option<&str>) -> Result<Vec<String>> {
+    let (anchor, place_after) = match (before, after) {
+        (Some(other), None) => (other, false),
+        (None, Some(other)) => (other, true),
+        _ => bail!("give exactly one of --before or --after"),
+    };
+    let moving = load(id)?;
+    let other = load(anchor)?;
+    if id == anchor || moving.parent_id() != other.parent_id() {
+        bail!("{} and {} are not siblings", id, anchor);
+    }
+
+    let mut siblings: Vec<Issue> = load_all()?
+        .into_iter()
+        .filter(|i| i.parent_id() == moving.parent_id() && i.id != id)
+        .collect();
+    siblings.sort_by(order_by("rank")?);
+    let pos = siblings.iter().position(|i| i.id == anchor).unwrap();
+    siblings.insert(if place_after { pos + 1 } else { pos }, moving);
+
+    let mut order = Vec::new();
+    for (rank, mut sibling) in siblings.into_iter().enumerate() {
+        let rank = rank as u32 + 1;
+        if sibling.rank != Some(rank) {
+            sibling.rank = Some(rank);
+            save(&sibling)?;
+        }
+        order.push(sibling.id);
+    }
+    outln!("{}", order.join(", "));
+    Ok(order)
+}
+
+/// Tick or untick checklist items by number, returning the checklist progress
+pub fn check(id: &str, items: &[usize], checked: bool) -> Result<(usize, usize)> {
+    let mut issue = load(id)?;
+    for &n in items {
+        issue.set_checked(n, checked)?;
+    }
+    save(&issue)?;
+    let checklist = issue.checklist();
+    let done = checklist.iter().filter(|i| i.checked).count();
+    outln!("{} | checklist {}/{}", id, done, checklist.len());
+    Ok((done, checklist.len()))
+}
+
+/// Set or clear an issue's estimate
+pub fn estimate(id: &str, value: &str) -> Result<Option<f64>> {
+    let unit = Config::load()?.estimate.unit;
     let mut issue = load(id)?;
-    issue.state = State::Closed;
+    issue.estimate = match value.trim() {
+        "none" => None,
+        v => Some(unit.parse(v)?),
+    };
     save(&issue)?;
-    println!("{} | >>> {}", id, message);
+    match issue.estimate {
+        Some(e) => outln!("{} | estimate {}", id, unit.format(e)),
+        None => outln!("{} | estimate cleared", id),
+    }
+    Ok(issue.estimate)
+}
+
+/// Log time spent on an issue by `who`, or the current author
+pub fn track(id: &str, spent: &str, message: &str, who: Option<&str>) -> Result<WorkEntry> {
+    let by = Config::load()?.author.resolve(who.unwrap_or("@me"))?;
+    let minutes = parse_duration(spent)?.num_minutes();
+    if minutes <= 0 {
+        bail!("tracked time must be at least one minute");
+    }
+    let mut issue = load(id)?;
+    let entry = WorkEntry {
+        at: Utc::now().trunc_subsecs(0),
+        by,
+        minutes,
+        message: message.to_string(),
+    };
+    issue.work.push(entry.clone());
+    save(&issue)?;
+    outln!(
+        "{} | {} by {}, {} in total",
+        id,
+        format_minutes(minutes),
+        entry.by,
+        format_minutes(issue.spent_minutes())
+    );
+    Ok(entry)
+}
+
+/// Print summary reports
+pub fn report(args: crate::ReportArgs) -> Result<()> {
+    match args.kind {
+        crate::ReportKind::Time { since, until } => report_time(since, until),
+    }
+}
+
+/// Time logged between two local dates, totalled per label and per assignee of the issue it was logged on
+fn report_time(since: Option<NaiveDate>, until: Option<NaiveDate>) -> Result<()> {
+    let mut total = 0;
+    let mut by_label: BTreeMap<String, i64> = BTreeMap::new();
+    let mut by_assignee: BTreeMap<String, i64> = BTreeMap::new();
+    for issue in load_all()? {
+        for work in &issue.work {
+            let day = work.at.with_timezone(&Local).date_naive();
+            if since.is_some_and(|s| day < s) || until.is_some_and(|u| day > u) {
+                continue;
+            }
+            total += work.minutes;
+            if issue.labels.is_empty() {
+                *by_label.entry("(unlabeled)".to_string()).or_default() += work.minutes;
+            }
+            for label in &issue.labels {
+                *by_label.entry(label.clone()).or_default() += work.minutes;
+            }
+            if issue.assignees.is_empty() {
+                *by_assignee.entry("(unassigned)".to_string()).or_default() += work.minutes;
+            }
+            for assignee in &issue.assignees {
+                *by_assignee.entry(assignee.clone()).or_default() += work.minutes;
+            }
+        }
+    }
+
+    let mut range = String::new();
+    if let Some(since) = since {
+        range.push_str(&format!(" since {}", since));
+    }
+    if let Some(until) = until {
+        range.push_str(&format!(" until {}", until));
+    }
+    outln!("time logged{}: {}", range, format_minutes(total));
+    for (title, totals) in [("by label", by_label), ("by assignee", by_assignee)] {
+        if totals.is_empty() {
+            continue;
+        }
+        outln!("\n{}", title);
+        for (name, minutes) in totals {
+            outln!("  {}: {}", name, format_minutes(minutes));
+        }
+    }
     Ok(())
 }
 
-pub fn reopen(id: &str, message: &str) -> Result<()> {
+/// Add or remove assignees; `@me` stands for the current author
+pub fn assign(id: &str, who: &[String], remove: bool) -> Result<Vec<String>> {
+    let author = Config::load()?.author;
     let mut issue = load(id)?;
-    if issue.state != State::Closed {
-        eprintln!("error: issue is not closed");
-        std::process::exit(1);
+    for name in who {
+        let name = author.resolve(name)?;
+        if remove {
+            issue.assignees.retain(|a| !a.eq_ignore_ascii_case(&name));
+            if issue.claim.as_ref().is_some_and(|c| c.by.eq_ignore_ascii_case(&name)) {
+                issue.claim = None;
+            }
+        } else if !issue.is_assigned_to(&name) {
+            issue.assignees.push(name);
+        }
+    }
+    save(&issue)?;
+    if issue.assignees.is_empty() {
+        outln!("{} | unassigned", id);
+    } else {
+        outln!("{} | assigned to {}", id, issue.assignees.join(", "));
     }
+    Ok(issue.assignees)
+}
+
+/// Result of `claim`: the claimed issue, if any, and the expired claims released on the way
+pub struct ClaimOutcome {
+    pub claimed: Option<Issue>,
+    pub released: Vec<(String, String)>,
+}
 
-    if let Some((parent, _)) = id.split_once('-') {
-        let p_issue = load(parent)?;
-        if p_issue.state == State::Closed {
-            eprintln!("error: parent issue closed");
-            std::process::exit(1);
+/// Atomically claim an issue for `who`: the given one, or the next ready issue nobody holds
+///
+/// The claimer is assigned and holds a lease; leases that have run out are
+/// released first, unassigning whoever let them lapse.
+pub fn claim(id: Option<&str>, who: Option<&str>, lease: Option<&str>) -> Result<ClaimOutcome> {
+    let config = Config::load()?;
+    let by = config.author.resolve(who.unwrap_or("@me"))?;
+    let lease = parse_duration(lease.unwrap_or(&config.claim.lease))?;
+
+    let _lock = crate::storage::lock()?;
+    let now = Utc::now().trunc_subsecs(0);
+    let released = release_expired_at(now)?;
+    let issues = load_all()?;
+    let mut issue = match id {
+        Some(id) => {
+            let issue = load(id)?;
+            if config.workflow.is_terminal(&issue.state) {
+                bail!("issue is closed");
+            }
+            if let Some(c) = issue.active_claim(now).filter(|c| !c.by.eq_ignore_ascii_case(&by)) {
+                bail!("already claimed by {} until {}", c.by, c.until.to_rfc3339_opts(SecondsFormat::Secs, true));
+            }
+            issue
         }
+        None => {
+            let free = ready_issues(&issues, &config.workflow).into_iter().find(|i| {
+                i.active_claim(now).is_none() && (i.assignees.is_empty() || i.is_assigned_to(&by))
+            });
+            match free {
+                Some(issue) => issue.clone(),
+                None => {
+                    outln!("no unclaimed issues");
+                    return Ok(ClaimOutcome { claimed: None, released });
+                }
+            }
+        }
+    };
+
+    let until = now + lease;
+    issue.claim = Some(Claim { by: by.clone(), until });
+    if !issue.is_assigned_to(&by) {
+        issue.assignees.push(by.clone());
     }
+    save(&issue)?;
+    outln!("{} | claimed by {} until {}", issue.id, by, until.to_rfc3339_opts(SecondsFormat::Secs, true));
+    view(crate::ViewArgs { id: issue.id.clone(), format: None, no_color: false, no_pager: true, raw: true, render: false })?;
+    Ok(ClaimOutcome { claimed: Some(issue), released })
+}
 
-    issue.state = State::Open;
-    let entry = format!("<<< {}", message);
-    issue.comments.push(entry);
+/// Drop a claim and unassign its holder, returning who held it
+pub fn release(id: &str) -> Result<String> {
+    let mut issue = load(id)?;
+    let Some(claim) = issue.claim.take() else {
+        bail!("issue is not claimed");
+    };
+    issue.assignees.retain(|a| !a.eq_ignore_ascii_case(&claim.by));
     save(&issue)?;
-    println!("{} | <<< {}", id, message);
+    outln!("{} | released by {}", id, claim.by);
+    Ok(claim.by)
+}
+
+/// Release every lapsed claim, returning `(id, holder)` pairs
+pub fn release_expired() -> Result<Vec<(String, String)>> {
+    let _lock = crate::storage::lock()?;
+    release_expired_at(Utc::now())
+}
+
+fn release_expired_at(now: DateTime<Utc>) -> Result<Vec<(String, String)>> {
+    let mut released = Vec::new();
+    for mut issue in load_all()? {
+        let Some(claim) = issue.claim.clone().filter(|c| c.until <= now) else {
+            continue;
+        };
+        issue.claim = None;
+        issue.assignees.retain(|a| !a.eq_ignore_ascii_case(&claim.by));
+        save(&issue)?;
+        outln!("{} | claim by {} expired", issue.id, claim.by);
+        released.push((issue.id, claim.by));
+    }
+    Ok(released)
+}
+
+/// List, show, define and assign milestones
+pub fn milestone(args: crate::MilestoneArgs) -> Result<()> {
+    use crate::milestones::{self, Milestone};
+    let workflow = Config::load()?.workflow;
+    let issues = load_all()?;
+    let by_id: HashMap<&str, &Issue> = issues.iter().map(|i| (i.id.as_str(), i)).collect();
+    let members = |name: &str| -> Vec<&Issue> {
+        issues
+            .iter()
+            .filter(|i| milestones::effective(i, &by_id) == Some(name))
+            .collect()
+    };
+    let line = |m: &Milestone| {
+        let progress = Progress::tally(members(&m.name), |i| workflow.is_terminal(&i.state));
+        let mut line = m.name.clone();
+        if !m.description.is_empty() {
+            line.push_str(&format!(" | {}", m.description));
+        }
+        if let Some(due) = m.due {
+            line.push_str(&format!(" (due {})", due));
+        }
+        format!("{} [{}/{}]", line, progress.done(), progress.total())
+    };
+
+    match args.action {
+        crate::MilestoneAction::Ls => {
+            for m in milestones::load()? {
+                outln!("{}", line(&m));
+            }
+        }
+        crate::MilestoneAction::Show { name } => {
+            let m = milestones::find(&name)?;
+            outln!("{}", line(&m));
+            let progress = Progress::tally(members(&name), |i| workflow.is_terminal(&i.state));
+            outln!(
+                "open: {}, completed: {}, wontfix: {}, duplicate: {}\n",
+                progress.open, progress.completed, progress.wontfix, progress.duplicate
+            );
+            for issue in members(&name) {
+                outln!("{}", summary(issue, &workflow));
+            }
+        }
+        crate::MilestoneAction::Create { name, description, due } => {
+            if name == "none" {
+                bail!("milestone name 'none' is reserved for clearing a milestone");
+            }
+            let mut all = milestones::load()?;
+            if all.iter().any(|m| m.name == name) {
+                bail!("milestone '{}' already exists", name);
+            }
+            all.push(Milestone { name: name.clone(), description, due });
+            milestones::save(&all)?;
+            outln!("{}", line(all.last().unwrap()));
+        }
+        crate::MilestoneAction::Set { id, name } => {
+            let mut issue = load(&id)?;
+            issue.milestone = match name.as_str() {
+                "none" => None,
+                name => Some(milestones::find(name)?.name),
+            };
+            save(&issue)?;
+            outln!("{} | milestone {}", id, name);
+        }
+    }
+    Ok(())
+}
+
+/// Kanban board of matching issues in columns by state, assignee, milestone, priority or a custom field
+pub fn board(args: crate::BoardArgs) -> Result<()> {
+    use crate::board::{Card, Column};
+    use std::io::IsTerminal;
+
+    let config = Config::load()?;
+    let (mut matched, all) = select(&config, &args.filter)?;
+    matched.sort_by(order_by("rank")?);
+    let by_id: HashMap<&str, &Issue> = all.iter().map(|i| (i.id.as_str(), i)).collect();
+    let none = "(none)";
+
+    // Column names for each issue, and the column order
+    let keys: Vec<Vec<String>> = match args.by.as_str() {
+        "state" => matched.iter().map(|i| vec![i.state.to_string()]).collect(),
+        "assignee" => matched.iter().map(|i| i.assignees.iter().map(|a| format!("@{}", a)).collect()).collect(),
+        "milestone" => matched
+            .iter()
+            .map(|i| crate::milestones::effective(i, &by_id).map(String::from).into_iter().collect())
+            .collect(),
+        "priority" => matched.iter().map(|i| i.priority.map(|p| format!("p{}", p)).into_iter().collect()).collect(),
+        field => {
+            config.field(field)?;
+            matched.iter().map(|i| i.extra.get(field).map(field_text).into_iter().collect()).collect()
+        }
+    };
+    let used: HashSet<&String> = keys.iter().flatten().collect();
+    let mut names: Vec<String> = match args.by.as_str() {
+        "state" => {
+            let state = args.filter.state.clone().unwrap_or(config.ls.state.clone());
+            let mut names = Vec::new();
+            for def in &config.workflow.states {
+                if config.workflow.filter_matches(&state, &State::from(def.name.as_str()))? {
+                    names.push(def.name.clone());
+                }
+            }
+            names
+        }
+        "milestone" => crate::milestones::load()?
+            .into_iter()
+            .map(|m| m.name)
+            .filter(|name| used.contains(name))
+            .collect(),
+        "priority" => {
+            let mut priorities: Vec<u8> = matched.iter().filter_map(|i| i.priority).collect();
+            priorities.sort();
+            priorities.dedup();
+            priorities.iter().map(|p| format!("p{}", p)).collect()
+        }
+        "assignee" => {
+            let mut names: Vec<String> = used.iter().map(|k| k.to_string()).collect();
+            names.sort();
+            names
+        }
+        field => {
+            let def = config.field(field)?;
+            let mut values: Vec<&serde_yaml::Value> = matched.iter().filter_map(|i| i.extra.get(field)).collect();
+            values.sort_by(|a, b| def.compare(a, b));
+            let mut names = def.values.clone();
+            for value in values.into_iter().map(field_text) {
+                if !names.contains(&value) {
+                    names.push(value);
+                }
+            }
+            names
+        }
+    };
+    // Issues without a value, or in a state outside the workflow, go after the declared columns
+    for key in keys.iter().flatten() {
+        if !names.contains(key) {
+            names.push(key.clone());
+        }
+    }
+    let mut columns: Vec<Column> = names.into_iter().map(|name| Column { name, cards: Vec::new() }).collect();
+    let mut unset = Column { name: none.to_string(), cards: Vec::new() };
+    for (issue, keys) in matched.iter().zip(&keys) {
+        let mut tags = Vec::new();
+        if let Some(priority) = issue.priority {
+            tags.push(format!("[p{}]", priority));
+        }
+        tags.extend(issue.assignees.iter().map(|a| format!("@{}", a)));
+        let card = || Card { id: issue.id.clone(), title: issue.title.clone(), labels: issue.labels.clone(), tags: tags.clone() };
+        if keys.is_empty() {
+            unset.cards.push(card());
+        }
+        for column in columns.iter_mut().filter(|c| keys.contains(&c.name)) {
+            column.cards.push(card());
+        }
+    }
+    if !unset.cards.is_empty() {
+        columns.push(unset);
+    }
+    if columns.is_empty() {
+        outln!("no matching issues");
+        return Ok(());
+    }
+
+    let width = match args.width {
+        Some(width) => Some(width),
+        None if std::io::stdout().is_terminal() => crossterm::terminal::size().ok().map(|(w, _)| usize::from(w)),
+        None => None,
+    };
+    let text = width
+        .and_then(|w| crate::board::render(&columns, w))
+        .unwrap_or_else(|| crate::board::plain(&columns));
+    out!("{}", text);
+    Ok(())
+}
+
+/// Find issues whose title, content or comments contain the query, ignoring case
+pub fn search(args: crate::SearchArgs) -> Result<Vec<Issue>> {
+    let config = Config::load()?;
+    let query = args.query.to_lowercase();
+    let mut found = Vec::new();
+    for issue in load_all()? {
+        let hit = issue.title.to_lowercase().contains(&query)
+            || issue.content.to_lowercase().contains(&query)
+            || issue.comments.iter().any(|c| c.to_lowercase().contains(&query));
+        if hit
+            && config.workflow.filter_matches(&args.state, &issue.state)?
+            && crate::labels::filter_matches(&args.labels, &issue.labels, &config.labels)
+        {
+            found.push(issue);
+        }
+    }
+    if args.format.unwrap_or(config.output.format) == Format::Json {
+        outln!("{}", serde_json::to_string_pretty(&found)?);
+    } else if found.is_empty() {
+        outln!("no matching issues");
+    } else {
+        for issue in &found {
+            outln!("{}", summary(issue, &config.workflow));
+        }
+    }
+    Ok(found)
+}
+
+/// Render issues in another format, to `--out` or stdout
+pub fn export(args: crate::ExportArgs) -> Result<()> {
+    let config = Config::load()?;
+    let workflow = &config.workflow;
+    let issues = load_all()?;
+    match args.format {
+        crate::ExportFormat::Ics { out, labels } => {
+            let open: Vec<&Issue> = issues
+                .iter()
+                .filter(|i| !workflow.is_terminal(&i.state))
+                .filter(|i| crate::labels::filter_matches(&labels, &i.labels, &config.labels))
+                .collect();
+            write_output(out.as_deref(), &crate::export::ics(&open, Utc::now()))
+        }
+        crate::ExportFormat::Md { id, comments, out } => {
+            if let Some(id) = &id {
+                load(id)?;
+            }
+            write_output(out.as_deref(), &crate::export::markdown(&issues, id.as_deref(), workflow, comments))
+        }
+        crate::ExportFormat::Html { out } => {
+            let pages = crate::site::build(&issues, &crate::milestones::load()?, &crate::labels::load()?, workflow);
+            for (path, html) in &pages {
+                let path = out.join(path);
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&path, html).with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+            outln!("wrote {} pages to {}", pages.len(), out.display());
+            Ok(())
+        }
+        crate::ExportFormat::Github { id, out, post, url } => {
+            if let Some(id) = &id {
+                load(id)?;
+            }
+            let base = match post {
+                true => Some(url.or(config.github.url.clone()).context(
+                    "--post needs a URL: pass --url or set github.url, e.g. https://api.github.com/repos/OWNER/REPO",
+                )?),
+                false => None,
+            };
+            export_github(&issues, id.as_deref(), workflow, &out, base.as_deref())
+        }
+        crate::ExportFormat::Csv { columns, tsv, out } => {
+            let columns = crate::spreadsheet::columns(&columns, &config)?;
+            let all: Vec<&Issue> = issues.iter().collect();
+            let text = crate::spreadsheet::write(&all, &columns, crate::spreadsheet::delimiter(tsv), &config)?;
+            write_output(out.as_deref(), &text)
+        }
+    }
+}
+
+/// What an import changed, for the audit log
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub created: Vec<Issue>,
+    pub updated: Vec<String>,
+    /// Issues moved to another state, with the state and any parents closed in cascade
+    pub moved: Vec<(String, State, Vec<String>)>,
+}
+
+/// A checked import row, ready to write
+enum Planned {
+    Create { key: Option<String>, parent: Option<String>, issue: Issue },
+    Update { issue: Issue, changed: bool },
+}
+
+/// Create or update issues from a file, recording each write in `report` as it happens
+///
+/// On error, `report` still holds what was written before it.
+pub fn import(args: crate::ImportArgs, report: &mut ImportReport) -> Result<()> {
+    let config = Config::load()?;
+    match args.format {
+        crate::ImportFormat::Csv { file, tsv, dry_run } => import_csv(&config, &file, tsv, dry_run, report),
+        crate::ImportFormat::Github { file } => import_github(&config, &file, report),
+    }
+}
+
+/// Issues from a GitHub or GitLab JSON export; ones imported before are skipped
+fn import_github(config: &Config, file: &std::path::Path, report: &mut ImportReport) -> Result<()> {
+    let data = fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let imported = crate::github::parse(&data, &config.workflow)?;
+    let existing: HashMap<String, String> = load_all()?
+        .into_iter()
+        .filter_map(|i| i.reference.clone().map(|r| (r, i.id)))
+        .collect();
+    for item in &imported {
+        crate::labels::check(&item.issue.labels, &config.labels)
+            .with_context(|| format!("issue {}", item.issue.reference.as_deref().unwrap_or_default()))?;
+    }
+
+    let mut ids: Vec<String> = Vec::new();
+    let mut skipped = 0;
+    for item in imported {
+        let mut issue = item.issue;
+        let reference = issue.reference.clone().unwrap_or_default();
+        if let Some(id) = existing.get(&reference) {
+            ids.push(id.clone());
+            skipped += 1;
+            continue;
+        }
+        issue.id = match item.parent.map(|p| &ids[p]) {
+            Some(parent) => next_child_id(parent, &config.id)?,
+            None => next_root_id(&config.id)?,
+        };
+        save(&issue)?;
+        outln!("{} <- {}", headline(&issue), reference);
+        ids.push(issue.id.clone());
+        report.created.push(issue);
+    }
+    outln!("imported {} created, {} already imported", report.created.len(), skipped);
+    Ok(())
+}
+
+/// Create or update issues from spreadsheet rows; every row is checked before anything is written
+fn import_csv(
+    config: &Config,
+    file: &std::path::Path,
+    tsv: bool,
+    dry_run: bool,
+    report: &mut ImportReport,
+) -> Result<()> {
+    let workflow = &config.workflow;
+    let data = fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let tsv = tsv || file.extension().is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+    let rows = crate::spreadsheet::read(&data, crate::spreadsheet::delimiter(tsv), config)?;
+    let issues: HashMap<String, Issue> = load_all()?.into_iter().map(|i| (i.id.clone(), i)).collect();
+
+    let mut planned = Vec::new();
+    let mut keys: HashSet<String> = HashSet::new();
+    let mut errors = Vec::new();
+    for row in &rows {
+        let id = row.value("id");
+        let parent = row.value("parent");
+        let result = (|| -> Result<(Planned, Option<State>)> {
+            let (plan, from) = match id.and_then(|id| issues.get(id)) {
+                Some(existing) => {
+                    if row.get("parent").is_some() && parent != existing.parent_id() {
+                        bail!("parent of {} cannot change (it is {})", existing.id, existing.parent_id().unwrap_or("none"));
+                    }
+                    let mut issue = existing.clone();
+                    crate::spreadsheet::apply(&mut issue, row, config)?;
+                    let changed = serde_yaml::to_string(&issue)? != serde_yaml::to_string(existing)?;
+                    let from = existing.state.clone();
+                    (Planned::Update { issue, changed }, from)
+                }
+                None => {
+                    let Some(title) = row.value("title") else {
+                        bail!("title is required for new issues");
+                    };
+                    if let Some(parent) = parent {
+                        if !issues.contains_key(parent) && !keys.contains(parent) {
+                            bail!("unknown parent '{}' (expected an issue ID or the id of an earlier row)", parent);
+                        }
+                    }
+                    if let Some(key) = id {
+                        if !keys.insert(key.to_string()) {
+                            bail!("id '{}' is used by an earlier row", key);
+                        }
+                    }
+                    let mut issue = Issue {
+                        title: title.to_string(),
+                        state: workflow.initial_state(),
+                        ..Default::default()
+                    };
+                    crate::spreadsheet::apply(&mut issue, row, config)?;
+                    let from = issue.state.clone();
+                    let plan = Planned::Create {
+                        key: id.map(str::to_string),
+                        parent: parent.map(str::to_string),
+                        issue,
+                    };
+                    (plan, from)
+                }
+            };
+            let target = row.value("state").map(|s| workflow.state(s)).transpose()?.filter(|s| *s != from);
+            if let Some(target) = &target {
+                if !workflow.allows(&from, target) {
+                    bail!("transition from {} to {} is not allowed", from, target);
+                }
+            }
+            Ok((plan, target))
+        })();
+        match result {
+            Ok((plan, target)) => planned.push((row.line, plan, target)),
+            Err(e) => errors.push(format!("row {}: {:#}", row.line, e)),
+        }
+    }
+    if errors.is_empty() {
+        errors = check_moves(&planned, &issues, workflow);
+    }
+    if !errors.is_empty() {
+        bail!("{} row(s) failed, nothing imported\n{}", errors.len(), errors.join("\n"));
+    }
+
+    if dry_run {
+        for (_, plan, target) in &planned {
+            let (what, name) = match plan {
+                Planned::Create { issue, parent: Some(parent), .. } => ("create", format!("\"{}\" under {}", issue.title, parent)),
+                Planned::Create { issue, parent: None, .. } => ("create", format!("\"{}\"", issue.title)),
+                Planned::Update { issue, changed: true } => ("update", issue.id.clone()),
+                Planned::Update { issue, changed: false } if target.is_some() => ("move", issue.id.clone()),
+                Planned::Update { .. } => continue,
+            };
+            match target {
+                Some(target) => outln!("would {} {} -> {}", what, name, target),
+                None => outln!("would {} {}", what, name),
+            }
+        }
+        return Ok(());
+    }
+
+    // Row keys of new issues, mapped to the IDs they were given
+    let mut assigned: HashMap<String, String> = HashMap::new();
+    let mut moves = Vec::new();
+    for (_, plan, target) in planned {
+        let id = match plan {
+            Planned::Create { key, parent, mut issue } => {
+                let parent = parent.map(|p| assigned.get(&p).cloned().unwrap_or(p));
+                issue.id = match &parent {
+                    Some(parent) => next_child_id(parent, &config.id)?,
+                    None => next_root_id(&config.id)?,
+                };
+                save(&issue)?;
+                outln!("{}", headline(&issue));
+                if let Some(key) = key {
+                    assigned.insert(key, issue.id.clone());
+                }
+                report.created.push(issue.clone());
+                issue.id
+            }
+            Planned::Update { issue, changed } => {
+                if changed {
+                    save(&issue)?;
+                    outln!("{} | updated", issue.id);
+                    report.updated.push(issue.id.clone());
+                }
+                issue.id
+            }
+        };
+        if let Some(target) = target {
+            moves.push((id, target));
+        }
+    }
+    // Reopen parents before their sub-issues, then close sub-issues before their parents
+    moves.sort_by_key(|(id, target)| {
+        let depth = id.matches('-').count() as i64;
+        match workflow.is_terminal(target) {
+            true => (true, -depth),
+            false => (false, depth),
+        }
+    });
+    for (id, target) in moves {
+        // A cascade from an earlier move may already have closed it
+        if load(&id)?.state == target {
+            continue;
+        }
+        let (state, parents) = status(&id, target.as_str(), Some("imported"), None, None)?;
+        report.moved.push((id, state, parents));
+    }
+    outln!("imported {} created, {} updated", report.created.len(), report.updated.len());
+    Ok(())
+}
+
+/// Check state changes against the parent/child and checklist rules, as they stand once every row is applied
+fn check_moves(planned: &[(u64, Planned, Option<State>)], issues: &HashMap<String, Issue>, workflow: &Workflow) -> Vec<String> {
+    // Issues by ID or row key, with their state after the import and their direct sub-issues
+    let mut states: HashMap<String, State> = issues.iter().map(|(id, i)| (id.clone(), i.state.clone())).collect();
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for issue in issues.values() {
+        if let Some(parent) = issue.parent_id() {
+            children.entry(parent.to_string()).or_default().push(issue.id.clone());
+        }
+    }
+    let mut names = Vec::new();
+    for (line, plan, target) in planned {
+        let (name, issue) = match plan {
+            Planned::Create { key, parent, issue } => {
+                let name = key.clone().unwrap_or_else(|| format!("row {}", line));
+                if let Some(parent) = parent {
+                    children.entry(parent.clone()).or_default().push(name.clone());
+                }
+                (name, issue)
+            }
+            Planned::Update { issue, .. } => (issue.id.clone(), issue),
+        };
+        states.insert(name.clone(), target.clone().unwrap_or_else(|| issue.state.clone()));
+        names.push(name);
+    }
+
+    let mut errors = Vec::new();
+    for ((line, plan, target), name) in planned.iter().zip(&names) {
+        let Some(target) = target else { continue };
+        let (issue, parent) = match plan {
+            Planned::Create { parent, issue, .. } => (issue, parent.as_deref()),
+            Planned::Update { issue, .. } => (issue, issue.parent_id()),
+        };
+        let error = if workflow.is_terminal(target) {
+            let unchecked = unchecked_items(issue, workflow, None);
+            let pending = children.get(name).into_iter().flatten().any(|c| states.get(c).is_some_and(|s| !workflow.is_terminal(s)));
+            if unchecked > 0 {
+                Some(format!("{} checklist item(s) still unchecked", unchecked))
+            } else if pending {
+                Some("child issues are still pending".to_string())
+            } else {
+                None
+            }
+        } else if workflow.is_terminal(&issue.state) && parent.and_then(|p| states.get(p)).is_some_and(|s| workflow.is_terminal(s)) {
+            Some("parent issue closed".to_string())
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            errors.push(format!("row {}: {}", line, error));
+        }
+    }
+    errors
+}
+
+/// Write one request body per open issue plus `mapping.json`, publishing them when `base` is given
+///
+/// Sub-issues go first, so a published parent can link them by number. Issues the mapping already
+/// records as published are not posted again.
+fn export_github(
+    issues: &[Issue],
+    root: Option<&str>,
+    workflow: &Workflow,
+    out: &std::path::Path,
+    base: Option<&str>,
+) -> Result<()> {
+    use crate::github::Mapping;
+    let mapping_path = out.join("mapping.json");
+    // Issues published by an earlier export, with or without `--post` this time
+    let mut published: HashMap<String, Mapping> = HashMap::new();
+    if mapping_path.exists() {
+        let previous: Vec<Mapping> = serde_json::from_str(&fs::read_to_string(&mapping_path)?)
+            .with_context(|| format!("Failed to parse {}", mapping_path.display()))?;
+        published.extend(previous.into_iter().filter(|m| m.number.is_some()).map(|m| (m.id.clone(), m)));
+    }
+    let mut numbers: HashMap<String, u64> = published.iter().filter_map(|(id, m)| Some((id.clone(), m.number?))).collect();
+    let mut selected: Vec<&Issue> = issues
+        .iter()
+        .filter(|i| root.is_none_or(|r| i.id == r || i.id.starts_with(&format!("{}-", r))))
+        .filter(|i| !workflow.is_terminal(&i.state))
+        .collect();
+    selected.sort_by_key(|i| std::cmp::Reverse(i.id.matches('-').count()));
+    fs::create_dir_all(out)?;
+
+    let token = std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty());
+    let mut mapping = Vec::new();
+    let mut failure = None;
+    for issue in selected {
+        let payload = crate::github::payload(issue, issues, workflow, &numbers);
+        let file = format!("{}.json", issue.id);
+        fs::write(out.join(&file), serde_json::to_string_pretty(&payload)? + "\n")?;
+        let mut entry = Mapping { id: issue.id.clone(), file, ..Default::default() };
+        if let Some(previous) = published.remove(&issue.id) {
+            entry.number = previous.number;
+            entry.url = previous.url;
+        } else if let Some(base) = base.filter(|_| failure.is_none()) {
+            match crate::github::post(base, token.as_deref(), &payload) {
+                Ok((number, url)) => {
+                    outln!("{} -> #{}", issue.id, number);
+                    numbers.insert(issue.id.clone(), number);
+                    entry.number = Some(number);
+                    entry.url = url;
+                }
+                Err(e) => failure = Some(e.context(format!("publishing {}", issue.id))),
+            }
+        }
+        mapping.push(entry);
+    }
+    mapping.sort_by(|a, b| a.id.cmp(&b.id));
+    fs::write(&mapping_path, serde_json::to_string_pretty(&mapping)? + "\n")?;
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    outln!("wrote {} payloads to {}", mapping.len(), out.display());
+    Ok(())
+}
+
+fn write_output(out: Option<&std::path::Path>, text: &str) -> Result<()> {
+    match out {
+        Some(path) => {
+            fs::write(path, text)?;
+            outln!("wrote {}", path.display());
+        }
+        None => out!("{}", text),
+    }
+    Ok(())
+}
+
+/// List, define, rename, merge and delete labels, returning how many issues changed
+pub fn label(args: crate::LabelArgs) -> Result<usize> {
+    use crate::labels::{self, LabelDef};
+    let rules = Config::load()?.labels;
+    let issues = load_all()?;
+    let mut registry = labels::load()?;
+    let position = |registry: &[LabelDef], name: &str| registry.iter().position(|d| rules.matches(&d.name, name));
+
+    // Swap `from` labels for `to` on every issue, checking scopes before anything is written
+    let relabel = |from: &[String], to: Option<&str>| -> Result<usize> {
+        let mut changed = Vec::new();
+        for issue in &issues {
+            if !issue.labels.iter().any(|l| from.iter().any(|f| rules.matches(l, f))) {
+                continue;
+            }
+            let mut issue = issue.clone();
+            let mut kept: Vec<String> = Vec::new();
+            for label in &issue.labels {
+                let label = if from.iter().any(|f| rules.matches(label, f)) {
+                    to.map(str::to_string)
+                } else {
+                    Some(label.clone())
+                };
+                if let Some(label) = label.filter(|l| !kept.iter().any(|k| rules.matches(k, l))) {
+                    kept.push(label);
+                }
+            }
+            issue.labels = kept;
+            labels::check_scopes(&issue.labels, &rules).with_context(|| format!("issue {}", issue.id))?;
+            changed.push(issue);
+        }
+        for issue in &changed {
+            save(issue)?;
+        }
+        Ok(changed.len())
+    };
+
+    match args.action {
+        crate::LabelAction::Ls => {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for issue in &issues {
+                for label in &issue.labels {
+                    let name = match position(&registry, label) {
+                        Some(i) => registry[i].name.clone(),
+                        None => label.clone(),
+                    };
+                    *counts.entry(name).or_default() += 1;
+                }
+            }
+            let mut names: Vec<String> = registry.iter().map(|d| d.name.clone()).collect();
+            names.extend(counts.keys().filter(|n| position(&registry, n).is_none()).cloned());
+            names.sort();
+            for name in names {
+                let mut line = format!("{} ({})", name, counts.get(&name).copied().unwrap_or_default());
+                match position(&registry, &name).map(|i| &registry[i]) {
+                    Some(def) => {
+                        if let Some(color) = &def.color {
+                            line.push_str(&format!(" {}", color));
+                        }
+                        if !def.description.is_empty() {
+                            line.push_str(&format!(" | {}", def.description));
+                        }
+                    }
+                    None => line.push_str(" [unregistered]"),
+                }
+                outln!("{}", line);
+            }
+            Ok(0)
+        }
+        crate::LabelAction::Create { name, color, description } => {
+            labels::validate_name(&name)?;
+            if let Some(color) = &color {
+                labels::validate_color(color)?;
+            }
+            rules.check(std::slice::from_ref(&name))?;
+            if position(&registry, &name).is_some() {
+                bail!("label '{}' already exists", name);
+            }
+            registry.push(LabelDef { name: name.clone(), color, description });
+            labels::save(&registry)?;
+            outln!("{} | created", name);
+            Ok(0)
+        }
+        crate::LabelAction::Rename { from, to } => {
+            labels::validate_name(&to)?;
+            rules.check(std::slice::from_ref(&to))?;
+            if position(&registry, &to).is_some() && !rules.matches(&from, &to) {
+                bail!("label '{}' already exists (use `label merge` to combine them)", to);
+            }
+            let count = relabel(std::slice::from_ref(&from), Some(&to))?;
+            match position(&registry, &from) {
+                Some(i) => registry[i].name = to.clone(),
+                None if count == 0 => bail!("unknown label '{}'", from),
+                None => {}
+            }
+            labels::save(&registry)?;
+            outln!("{} -> {} | {} issue(s) updated", from, to, count);
+            Ok(count)
+        }
+        crate::LabelAction::Merge { from, into } => {
+            labels::validate_name(&into)?;
+            rules.check(std::slice::from_ref(&into))?;
+            let from: Vec<String> = from.into_iter().filter(|f| !rules.matches(f, &into)).collect();
+            let count = relabel(&from, Some(&into))?;
+            registry.retain(|d| !from.iter().any(|f| rules.matches(&d.name, f)));
+            labels::save(&registry)?;
+            outln!("{} -> {} | {} issue(s) updated", from.join(", "), into, count);
+            Ok(count)
+        }
+        crate::LabelAction::Delete { name } => {
+            let count = relabel(std::slice::from_ref(&name), None)?;
+            match position(&registry, &name) {
+                Some(i) => {
+                    registry.remove(i);
+                }
+                None if count == 0 => bail!("unknown label '{}'", name),
+                None => {}
+            }
+            labels::save(&registry)?;
+            outln!("{} | deleted from {} issue(s)", name, count);
+            Ok(count)
+        }
+    }
+}
+
+/// Print or update configuration values
+pub fn config(args: crate::ConfigArgs) -> Result<()> {
+    match args.action {
+        crate::ConfigAction::Get { key, global } => match crate::config::get(&key, global)? {
+            Some(serde_yaml::Value::String(s)) => outln!("{}", s),
+            Some(value) => out!("{}", serde_yaml::to_string(&value)?),
+            None => anyhow::bail!("config key '{}' is not set", key),
+        },
+        crate::ConfigAction::Set { key, value, global } => {
+            crate::config::set(&key, &value, global)?;
+            outln!("{} = {}", key, value);
+        }
+    }
     Ok(())
 }
 
+/// Rewrite every issue file in the current schema, returning how many changed
+pub fn migrate(args: crate::MigrateArgs) -> Result<usize> {
+    let mut changed = 0;
+    for id in crate::storage::all_ids()? {
+        let path = path_for(&id)?;
+        let before = fs::read_to_string(&path)?;
+        let after = crate::storage::render(&crate::storage::parse(&before)?)?;
+        if before == after {
+            continue;
+        }
+        changed += 1;
+        if args.dry_run {
+            let diff = similar::TextDiff::from_lines(&before, &after);
+            let name = path.display().to_string();
+            out!("{}", diff.unified_diff().header(&name, &name));
+        } else {
+            fs::write(&path, after)?;
+            outln!("{} | migrated", id);
+        }
+    }
+    if args.dry_run {
+        outln!("{} issue(s) would be migrated", changed);
+    } else {
+        outln!("{} issue(s) migrated", changed);
+    }
+    Ok(changed)
+}
+
 /*
 // JSON schema for the plan command:
 // {
 //   "title": "Parent Issue Title",
 //   "content": "Parent issue description.",
+//   "template": "epic",
 //   "labels": ["feature", "batch"],
+//   "auto_close": true,
+//   "milestone": "v1.2",
+//   "due": "2026-03-31",
+//   "start": "2026-03-01",
+//   "estimate": "8h",
+//   "fields": {"component": "parser", "severity": "high"},
 //   "sub_issues": [
 //     {
 //       "title": "Sub-issue 1",
 //       "content": "Details for sub-issue 1.",
-//       "labels": ["bug"]
+//       "labels": ["bug"],
+//       "due": "2026-03-15"
 //     },
 //     ...
 //   ]
@@ -233,18 +1941,43 @@ pub fn reopen(id: &str, message: &str) -> Result<()> {
 
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+/// A sub-issue in a plan
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct IssueSpec {
     pub title: String,
+    /// Body; required unless a template supplies it
+    #[serde(default)]
     pub content: String,
+    /// Template from `.issues/templates/`, or `none`
+    pub template: Option<String>,
     pub labels: Option<Vec<String>>,
+    pub due: Option<NaiveDate>,
+    pub start: Option<NaiveDate>,
+    /// Expected effort: a number, or text such as `1h30m`
+    pub estimate: Option<serde_json::Value>,
+    /// Custom field values, by name
+    pub fields: Option<BTreeMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Deserialize)]
+/// A parent issue and its sub-issues, created together or not at all
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct PlanSpec {
     pub title: String,
+    /// Body; required unless a template supplies it
+    #[serde(default)]
     pub content: String,
+    /// Template from `.issues/templates/`, or `none`
+    pub template: Option<String>,
     pub labels: Option<Vec<String>>,
+    /// Close the parent once its last sub-issue closes
+    pub auto_close: Option<bool>,
+    pub milestone: Option<String>,
+    pub due: Option<NaiveDate>,
+    pub start: Option<NaiveDate>,
+    /// Expected effort: a number, or text such as `1h30m`
+    pub estimate: Option<serde_json::Value>,
+    /// Custom field values, by name
+    pub fields: Option<BTreeMap<String, serde_json::Value>>,
     pub sub_issues: Vec<IssueSpec>,
 }
 
@@ -268,27 +2001,76 @@ pub fn plan(args: crate::PlanArgs) -> Result<(String, String)> {
     let plan: PlanSpec = serde_json::from_str(&json_str)
         .map_err(|e| anyhow::anyhow!("Failed to parse plan JSON: {}", e))?;
 
-    // Create parent issue
     let parent_args = crate::CreateArgs {
         parent: None,
         title: plan.title.clone(),
         content: plan.content.clone(),
+        template: plan.template.clone(),
         label: plan.labels.clone(),
+        auto_close: plan.auto_close,
+        milestone: plan.milestone.clone(),
+        due: plan.due,
+        start: plan.start,
+        estimate: plan.estimate.as_ref().map(json_text),
+        field: field_assignments(plan.fields.as_ref()),
     };
+    let mut sub_args: Vec<crate::CreateArgs> = plan
+        .sub_issues
+        .iter()
+        .map(|sub| crate::CreateArgs {
+            title: sub.title.clone(),
+            content: sub.content.clone(),
+            template: sub.template.clone(),
+            label: sub.labels.clone(),
+            due: sub.due,
+            start: sub.start,
+            estimate: sub.estimate.as_ref().map(json_text),
+            field: field_assignments(sub.fields.as_ref()),
+            ..Default::default()
+        })
+        .collect();
+
+    // Validate every issue up front so a bad sub-issue doesn't leave a partial plan
+    let config = Config::load()?;
+    for args in std::iter::once(&parent_args).chain(&sub_args) {
+        let labels = args.label.clone().unwrap_or_default();
+        crate::labels::check(&labels, &config.labels)?;
+        check_dates(args.start, args.due)?;
+        set_fields(&config, &mut BTreeMap::new(), &args.field)?;
+        if let Some(estimate) = &args.estimate {
+            config.estimate.unit.parse(estimate)?;
+        }
+        templated_content(&config, args, &labels)
+            .with_context(|| format!("issue '{}'", args.title))?;
+    }
+
+    // Create parent issue
     let parent_issue = crate::commands::create(parent_args)?;
     let parent_id = parent_issue.id.clone();
     let parent_title = parent_issue.title.clone();
 
     // Create sub-issues
-    for sub in &plan.sub_issues {
-        let sub_args = crate::CreateArgs {
-            parent: Some(parent_id.clone()),
-            title: sub.title.clone(),
-            content: sub.content.clone(),
-            label: sub.labels.clone(),
-        };
-        let _ = crate::commands::create(sub_args)?;
+    for mut args in sub_args.drain(..) {
+        args.parent = Some(parent_id.clone());
+        let _ = crate::commands::create(args)?;
     }
 
     Ok((parent_id, parent_title))
 }
+
+/// Plan values may be JSON numbers (`3`), booleans or strings (`"1h30m"`)
+fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Plan `fields` objects as `KEY=VALUE` assignments
+fn field_assignments(fields: Option<&BTreeMap<String, serde_json::Value>>) -> Vec<String> {
+    fields
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| format!("{}={}", key, json_text(value)))
+        .collect()
+}
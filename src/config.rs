@@ -0,0 +1,672 @@
+// Repository and user-level configuration
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::models::State;
+use crate::storage::STORAGE_DIR;
+
+pub const CONFIG_FILE: &str = "config.yaml";
+
+/// Effective configuration: built-in defaults, overridden by the user file, then the repo file
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub ls: LsDefaults,
+    pub id: IdFormat,
+    pub labels: LabelRules,
+    pub author: Author,
+    pub output: Output,
+    pub workflow: Workflow,
+    pub claim: ClaimRules,
+    pub estimate: EstimateRules,
+    pub github: GithubSettings,
+    /// Custom issue fields, by name
+    pub fields: BTreeMap<String, FieldDef>,
+}
+
+/// A custom issue field and the type its values must have
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldDef {
+    #[serde(rename = "type")]
+    pub kind: FieldType,
+    /// Allowed values of an `enum` field, in sort order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Enum,
+    Int,
+    Date,
+    Bool,
+}
+
+impl std::fmt::Display for FieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldType::String => write!(f, "string"),
+            FieldType::Enum => write!(f, "enum"),
+            FieldType::Int => write!(f, "int"),
+            FieldType::Date => write!(f, "date"),
+            FieldType::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+impl FieldDef {
+    /// Parse a raw value into the field's type
+    pub fn parse(&self, name: &str, raw: &str) -> Result<Value> {
+        let raw = raw.trim();
+        let invalid = || anyhow::anyhow!("invalid value '{}' for {} field '{}'", raw, self.kind, name);
+        match self.kind {
+            FieldType::String => Ok(Value::String(raw.to_string())),
+            FieldType::Enum => match self.values.iter().find(|v| v.eq_ignore_ascii_case(raw)) {
+                Some(value) => Ok(Value::String(value.clone())),
+                None => bail!("{} (expected one of: {})", invalid(), self.values.join(", ")),
+            },
+            FieldType::Int => raw.parse::<i64>().map(Value::from).map_err(|_| invalid()),
+            FieldType::Date => chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .map(|date| Value::String(date.to_string()))
+                .map_err(|_| invalid()),
+            FieldType::Bool => match raw.to_lowercase().as_str() {
+                "true" | "yes" => Ok(Value::Bool(true)),
+                "false" | "no" => Ok(Value::Bool(false)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+
+    /// Order two stored values: enums by declared position, the rest by value
+    pub fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        match (self.kind, a, b) {
+            (FieldType::Enum, Value::String(a), Value::String(b)) => {
+                let position = |v: &String| self.values.iter().position(|x| x == v);
+                position(a).cmp(&position(b))
+            }
+            (FieldType::Int, _, _) => a.as_i64().cmp(&b.as_i64()),
+            (FieldType::Bool, _, _) => a.as_bool().cmp(&b.as_bool()),
+            _ => a.as_str().cmp(&b.as_str()),
+        }
+    }
+}
+
+/// How issue estimates are measured
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EstimateRules {
+    pub unit: EstimateUnit,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EstimateUnit {
+    #[default]
+    Hours,
+    Points,
+}
+
+impl EstimateUnit {
+    /// Parse an estimate: a number, `5h`/`1h30m` for hours, or `3pt` for points
+    pub fn parse(&self, text: &str) -> Result<f64> {
+        let text = text.trim();
+        let number = match self {
+            EstimateUnit::Hours => text.strip_suffix('h').unwrap_or(text),
+            EstimateUnit::Points => text.strip_suffix("pt").unwrap_or(text),
+        };
+        let value = match (number.parse::<f64>(), self) {
+            (Ok(value), _) => value,
+            (Err(_), EstimateUnit::Hours) => crate::models::parse_duration(text)
+                .map(|d| d.num_minutes() as f64 / 60.0)
+                .map_err(|_| anyhow::anyhow!("invalid estimate '{}' (expected hours, e.g. 4 or 1h30m)", text))?,
+            (Err(_), EstimateUnit::Points) => bail!("invalid estimate '{}' (expected points, e.g. 3)", text),
+        };
+        if !value.is_finite() || value < 0.0 {
+            bail!("invalid estimate '{}'", text);
+        }
+        Ok(value)
+    }
+
+    /// Render an estimate with its unit, e.g. `1.5h` or `3pt`
+    pub fn format(&self, value: f64) -> String {
+        let value = (value * 100.0).round() / 100.0;
+        match self {
+            EstimateUnit::Hours => format!("{}h", value),
+            EstimateUnit::Points => format!("{}pt", value),
+        }
+    }
+}
+
+/// Where `export github --post` publishes issues
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubSettings {
+    /// Repository API base, e.g. `https://api.github.com/repos/OWNER/REPO`
+    pub url: Option<String>,
+}
+
+/// Settings for `claim`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClaimRules {
+    /// How long a claim lasts before it is released, e.g. `1h` or `30m`
+    pub lease: String,
+}
+
+impl Default for ClaimRules {
+    fn default() -> Self {
+        ClaimRules { lease: "1h".into() }
+    }
+}
+
+/// Defaults for `ls` when the matching flag is not given
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LsDefaults {
+    pub state: String,
+    pub label: Option<String>,
+    pub sort: String,
+    pub order: String,
+}
+
+impl Default for LsDefaults {
+    fn default() -> Self {
+        LsDefaults {
+            state: "open".into(),
+            label: None,
+            sort: "id".into(),
+            order: "asc".into(),
+        }
+    }
+}
+
+/// Shape of generated issue IDs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdFormat {
+    /// Number of zero-padded digits per ID segment
+    pub width: usize,
+}
+
+impl Default for IdFormat {
+    fn default() -> Self {
+        IdFormat { width: 3 }
+    }
+}
+
+impl IdFormat {
+    pub fn format(&self, n: usize) -> String {
+        format!("{:0width$}", n, width = self.width)
+    }
+}
+
+/// Rules applied to issue labels
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LabelRules {
+    /// When non-empty, only these labels may be used
+    pub allowed: Vec<String>,
+    /// Compare labels exactly instead of trimmed and lowercased
+    pub case_sensitive: bool,
+    /// Only accept labels defined with `git issue label create`
+    pub strict: bool,
+}
+
+impl LabelRules {
+    /// Whether two labels are the same under the configured casing rules
+    pub fn matches(&self, a: &str, b: &str) -> bool {
+        if self.case_sensitive {
+            a == b
+        } else {
+            a.trim().to_lowercase() == b.trim().to_lowercase()
+        }
+    }
+
+    /// Match a label against a filter, where `scope::*` matches every label in that scope
+    pub fn filter_matches(&self, filter: &str, label: &str) -> bool {
+        match filter.strip_suffix("::*") {
+            Some(scope) => crate::labels::scope(label).is_some_and(|s| self.matches(s, scope)),
+            None => self.matches(label, filter),
+        }
+    }
+
+    /// Reject labels outside the allowed list, if one is configured
+    pub fn check(&self, labels: &[String]) -> Result<()> {
+        if self.allowed.is_empty() {
+            return Ok(());
+        }
+        for label in labels {
+            if !self.allowed.iter().any(|a| self.matches(a, label)) {
+                bail!(
+                    "label '{}' is not allowed (allowed: {})",
+                    label,
+                    self.allowed.join(",")
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Identity recorded in the audit log
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Author {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Author {
+    /// Name from `$GIT_ISSUE_AUTHOR`, then config, then `git config user.name`
+    pub fn name(&self) -> Option<String> {
+        std::env::var("GIT_ISSUE_AUTHOR")
+            .ok()
+            .filter(|n| !n.trim().is_empty())
+            .or_else(|| self.name.clone())
+            .or_else(|| {
+                let out = std::process::Command::new("git")
+                    .args(["config", "user.name"])
+                    .output()
+                    .ok()?;
+                let name = String::from_utf8(out.stdout).ok()?.trim().to_string();
+                (!name.is_empty()).then_some(name)
+            })
+    }
+
+    /// Resolve an assignee argument, where `@me` stands for the current author
+    pub fn resolve(&self, who: &str) -> Result<String> {
+        if who == "@me" {
+            return self.name().context("cannot resolve @me: set author.name or GIT_ISSUE_AUTHOR");
+        }
+        Ok(who.trim_start_matches('@').to_string())
+    }
+
+    /// Name and email as recorded in the audit log
+    pub fn identity(&self) -> Option<String> {
+        let name = self.name()?;
+        match &self.email {
+            Some(email) => Some(format!("{} <{}>", name, email)),
+            None => Some(name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Output {
+    pub format: Format,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Workflow states and the transitions allowed between them
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Workflow {
+    pub states: Vec<StateDef>,
+    /// Allowed targets per state; a state without an entry may move anywhere
+    pub transitions: BTreeMap<String, Vec<String>>,
+    /// State for new issues [default: first non-terminal state]
+    pub initial: Option<String>,
+    /// State set by `close` [default: first terminal state]
+    pub close: Option<String>,
+    /// State set by `reopen` [default: the initial state]
+    pub reopen: Option<String>,
+    /// Close a parent once its last open sub-issue closes, unless the parent opts out
+    pub auto_close_parents: bool,
+    /// Refuse completed closes while checklist items in the content are unticked
+    pub require_checklist: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateDef {
+    pub name: String,
+    /// Terminal states count as closed
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+impl Default for Workflow {
+    fn default() -> Self {
+        Workflow {
+            states: vec![
+                StateDef { name: "open".into(), terminal: false },
+                StateDef { name: "closed".into(), terminal: true },
+            ],
+            transitions: BTreeMap::new(),
+            initial: None,
+            close: None,
+            reopen: None,
+            auto_close_parents: false,
+            require_checklist: false,
+        }
+    }
+}
+
+impl Workflow {
+    fn find(&self, name: &str) -> Option<&StateDef> {
+        self.states.iter().find(|s| s.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    fn first(&self, terminal: bool) -> State {
+        self.states
+            .iter()
+            .find(|s| s.terminal == terminal)
+            .map(|s| State::from(s.name.as_str()))
+            .unwrap_or_else(|| if terminal { State::closed() } else { State::open() })
+    }
+
+    /// Resolve a user-supplied state name against the configured states
+    pub fn state(&self, name: &str) -> Result<State> {
+        match self.find(name) {
+            Some(def) => Ok(State::from(def.name.as_str())),
+            None => bail!(
+                "unknown state '{}' (states: {})",
+                name,
+                self.states.iter().map(|s| s.name.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    /// Whether a state counts as closed; unknown states count as open
+    pub fn is_terminal(&self, state: &State) -> bool {
+        self.find(state.as_str()).is_some_and(|s| s.terminal)
+    }
+
+    /// Match a state against an `ls --state` filter: `all`, a state name, or the `open`/`closed` categories
+    pub fn filter_matches(&self, filter: &str, state: &State) -> Result<bool> {
+        if filter == "all" {
+            return Ok(true);
+        }
+        if self.find(filter).is_some() {
+            return Ok(State::from(filter) == *state);
+        }
+        match filter {
+            "open" => Ok(!self.is_terminal(state)),
+            "closed" => Ok(self.is_terminal(state)),
+            _ => self.state(filter).map(|_| false),
+        }
+    }
+
+    pub fn initial_state(&self) -> State {
+        self.initial.as_deref().map(State::from).unwrap_or_else(|| self.first(false))
+    }
+
+    pub fn close_state(&self) -> State {
+        self.close.as_deref().map(State::from).unwrap_or_else(|| self.first(true))
+    }
+
+    pub fn reopen_state(&self) -> State {
+        self.reopen.as_deref().map(State::from).unwrap_or_else(|| self.initial_state())
+    }
+
+    /// Whether moving from `from` to `to` is permitted
+    pub fn allows(&self, from: &State, to: &State) -> bool {
+        match self.transitions.iter().find(|(k, _)| State::from(k.as_str()) == *from) {
+            Some((_, targets)) => targets.iter().any(|t| State::from(t.as_str()) == *to),
+            None => true,
+        }
+    }
+
+    /// Check that every referenced state exists and the defaults have the right category
+    pub fn validate(&self) -> Result<()> {
+        if !self.states.iter().any(|s| !s.terminal) || !self.states.iter().any(|s| s.terminal) {
+            bail!("workflow needs at least one terminal and one non-terminal state");
+        }
+        for (from, targets) in &self.transitions {
+            self.state(from)?;
+            for to in targets {
+                self.state(to)?;
+            }
+        }
+        for (role, state, terminal) in [
+            ("initial", self.initial_state(), false),
+            ("close", self.close_state(), true),
+            ("reopen", self.reopen_state(), false),
+        ] {
+            self.state(state.as_str())?;
+            if self.is_terminal(&state) != terminal {
+                bail!("workflow.{} state '{}' has the wrong category", role, state);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Path of the repository config, `.issues/config.yaml`
+pub fn repo_path() -> PathBuf {
+    PathBuf::from(STORAGE_DIR).join(CONFIG_FILE)
+}
+
+/// Path of the user config, under `$XDG_CONFIG_HOME` or `~/.config`
+pub fn user_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("git-issue").join(CONFIG_FILE))
+}
+
+fn read_value(path: &PathBuf) -> Result<Value> {
+    if !path.exists() {
+        return Ok(Value::Mapping(Mapping::new()));
+    }
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config {}", path.display()))?;
+    let value: Value = serde_yaml::from_str(&data)
+        .with_context(|| format!("Failed to parse config {}", path.display()))?;
+    Ok(match value {
+        Value::Null => Value::Mapping(Mapping::new()),
+        v => v,
+    })
+}
+
+/// Recursively overlay `over` onto `base`, with `over` winning on conflicts
+fn merge(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Mapping(base), Value::Mapping(over)) => {
+            for (k, v) in over {
+                match base.get_mut(&k) {
+                    Some(existing) => merge(existing, v),
+                    None => {
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over,
+    }
+}
+
+fn layered() -> Result<Value> {
+    let mut value = Value::Mapping(Mapping::new());
+    if let Some(path) = user_path() {
+        merge(&mut value, read_value(&path)?);
+    }
+    merge(&mut value, read_value(&repo_path())?);
+    Ok(value)
+}
+
+impl Config {
+    /// Load the effective configuration
+    pub fn load() -> Result<Config> {
+        Config::from_value(layered()?).context("Invalid configuration")
+    }
+
+    fn from_value(value: Value) -> Result<Config> {
+        let config: Config = serde_yaml::from_value(value)?;
+        config.workflow.validate()?;
+        crate::models::parse_duration(&config.claim.lease).context("claim.lease")?;
+        for (name, def) in &config.fields {
+            if (def.kind == FieldType::Enum) == def.values.is_empty() {
+                bail!("fields.{}: `values` must be given for enum fields, and only for them", name);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Look up a custom field definition
+    pub fn field(&self, name: &str) -> Result<&FieldDef> {
+        match self.fields.get(name) {
+            Some(def) => Ok(def),
+            None => bail!(
+                "unknown field '{}' (fields: {})",
+                name,
+                self.fields.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    /// Parse `key=value` into a typed field value; an empty value means unset
+    pub fn parse_field(&self, assignment: &str) -> Result<(String, Option<Value>)> {
+        let Some((key, raw)) = assignment.split_once('=') else {
+            bail!("invalid field '{}' (expected KEY=VALUE)", assignment);
+        };
+        let key = key.trim();
+        let def = self.field(key)?;
+        if raw.trim().is_empty() {
+            return Ok((key.to_string(), None));
+        }
+        Ok((key.to_string(), Some(def.parse(key, raw)?)))
+    }
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |v, part| v.get(part))
+}
+
+fn assign(value: &mut Value, key: &str, new: Value) -> Result<()> {
+    let mut current = value;
+    let parts: Vec<&str> = key.split('.').collect();
+    for (i, part) in parts.iter().enumerate() {
+        let Value::Mapping(map) = current else {
+            bail!("'{}' is not a section", parts[..i].join("."));
+        };
+        if i == parts.len() - 1 {
+            map.insert((*part).into(), new);
+            return Ok(());
+        }
+        current = map
+            .entry((*part).into())
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+    }
+    Ok(())
+}
+
+fn target(global: bool) -> Result<PathBuf> {
+    if global {
+        user_path().context("cannot locate user config: HOME is not set")
+    } else {
+        Ok(repo_path())
+    }
+}
+
+/// Look up a dotted key, in the effective config or only the user file with `global`
+pub fn get(key: &str, global: bool) -> Result<Option<Value>> {
+    let value = if global {
+        read_value(&target(true)?)?
+    } else {
+        serde_yaml::to_value(Config::load()?)?
+    };
+    Ok(lookup(&value, key).cloned())
+}
+
+/// Set a dotted key in the repo file, or the user file with `global`
+pub fn set(key: &str, raw: &str, global: bool) -> Result<()> {
+    let path = target(global)?;
+    let mut value = read_value(&path)?;
+    let parsed: Value = serde_yaml::from_str(raw).unwrap_or_else(|_| Value::String(raw.into()));
+    assign(&mut value, key, parsed)?;
+    Config::from_value(value.clone()).with_context(|| format!("Invalid value for {}", key))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_yaml::to_string(&value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_overrides_nested_keys() {
+        let mut base: Value = serde_yaml::from_str("ls:\n  state: all\n  order: desc\n").unwrap();
+        let over: Value = serde_yaml::from_str("ls:\n  state: closed\n").unwrap();
+        merge(&mut base, over);
+        let config: Config = serde_yaml::from_value(base).unwrap();
+        assert_eq!(config.ls.state, "closed");
+        assert_eq!(config.ls.order, "desc");
+        assert_eq!(config.id.width, 3);
+    }
+
+    #[test]
+    fn test_workflow_categories_and_transitions() {
+        let config: Config = serde_yaml::from_str(
+            "workflow:\n  states:\n  - name: todo\n  - name: in-progress\n  - name: done\n    terminal: true\n  transitions:\n    todo: [in-progress]\n",
+        )
+        .unwrap();
+        let wf = &config.workflow;
+        wf.validate().unwrap();
+        assert_eq!(wf.initial_state(), State::from("todo"));
+        assert_eq!(wf.close_state(), State::from("done"));
+        assert!(wf.is_terminal(&State::from("done")));
+        assert!(!wf.allows(&State::from("todo"), &State::from("done")));
+        assert!(wf.allows(&State::from("in-progress"), &State::from("done")));
+        assert!(wf.state("blocked").is_err());
+    }
+
+    #[test]
+    fn test_label_rules() {
+        let rules = LabelRules {
+            allowed: vec!["bug".into()],
+            ..Default::default()
+        };
+        assert!(rules.check(&[" BUG ".into()]).is_ok());
+        assert!(rules.check(&["feature".into()]).is_err());
+        assert!(rules.filter_matches("Type::*", "type::bug"));
+        assert!(!rules.filter_matches("type::*", "bug"));
+    }
+
+    #[test]
+    fn test_field_types() {
+        let config: Config = serde_yaml::from_str(
+            "fields:\n  severity:\n    type: enum\n    values: [low, high]\n  count:\n    type: int\n",
+        )
+        .unwrap();
+        let (key, value) = config.parse_field("severity=HIGH").unwrap();
+        assert_eq!((key.as_str(), value), ("severity", Some(Value::from("high"))));
+        assert!(config.parse_field("severity=urgent").is_err());
+        assert!(config.parse_field("count=three").is_err());
+        assert_eq!(config.parse_field("count=").unwrap().1, None);
+        assert!(config.parse_field("owner=me").is_err());
+        let severity = config.field("severity").unwrap();
+        assert_eq!(severity.compare(&Value::from("low"), &Value::from("high")), Ordering::Less);
+    }
+
+    #[test]
+    fn test_estimate_units() {
+        assert_eq!(EstimateUnit::Hours.parse("1h30m").unwrap(), 1.5);
+        assert_eq!(EstimateUnit::Hours.parse("4").unwrap(), 4.0);
+        assert_eq!(EstimateUnit::Points.parse("3pt").unwrap(), 3.0);
+        assert!(EstimateUnit::Points.parse("1h").is_err());
+        assert!(EstimateUnit::Hours.parse("-2").is_err());
+        assert_eq!(EstimateUnit::Hours.format(1.5), "1.5h");
+        assert_eq!(EstimateUnit::Points.format(8.0), "8pt");
+    }
+}
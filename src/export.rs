@@ -0,0 +1,210 @@
+// Renderers for `git issue export`
+
+use crate::config::Workflow;
+use crate::models::Issue;
+use chrono::{DateTime, Days, Utc};
+use std::collections::HashMap;
+
+/// iCalendar feed with one all-day event per issue that has a due date
+///
+/// Lines end in CRLF and are folded at 75 octets as RFC 5545 requires.
+pub fn ics(issues: &[&Issue], now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//git-issue//git-issue//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for issue in issues {
+        let Some(due) = issue.due else { continue };
+        let end = due.checked_add_days(Days::new(1)).unwrap_or(due);
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@git-issue", issue.id),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+            format!("SUMMARY:{}", escape(&format!("{} | {}", issue.id, issue.title))),
+        ]);
+        if !issue.content.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(&issue.content)));
+        }
+        if !issue.labels.is_empty() {
+            let labels: Vec<String> = issue.labels.iter().map(|l| escape(l)).collect();
+            lines.push(format!("CATEGORIES:{}", labels.join(",")));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold(l) + "\r\n").collect()
+}
+
+/// Markdown document of `issues` (a whole store, in ID order) or of the subtree under `root`
+///
+/// A nested checklist of the hierarchy comes first, then one section per issue whose heading
+/// level follows its depth. Nothing time-dependent is included, so re-exports diff cleanly.
+pub fn markdown(issues: &[Issue], root: Option<&str>, workflow: &Workflow, comments: bool) -> String {
+    let by_id: HashMap<&str, &Issue> = issues.iter().map(|i| (i.id.as_str(), i)).collect();
+    let in_scope = |issue: &Issue| {
+        root.is_none_or(|r| issue.id == r || issue.id.starts_with(&format!("{}-", r)))
+    };
+    let selected: Vec<&Issue> = issues.iter().filter(|i| in_scope(i)).collect();
+    // Depth relative to the document, so a subtree export starts at the top level
+    let base = root.map_or(0, |r| r.matches('-').count());
+    let depth = |issue: &Issue| issue.id.matches('-').count() - base;
+
+    let mut out = match root.and_then(|r| by_id.get(r)) {
+        Some(issue) => format!("# {} {}\n\n", issue.id, issue.title),
+        None => "# Issues\n\n".to_string(),
+    };
+    for issue in &selected {
+        let mark = if workflow.is_terminal(&issue.state) { "x" } else { " " };
+        out.push_str(&format!("{}- [{}] {} {}\n", "  ".repeat(depth(issue)), mark, issue.id, issue.title));
+    }
+
+    for issue in &selected {
+        let level = (depth(issue) + 2).min(6);
+        out.push_str(&format!("\n{} {} {}\n\n", "#".repeat(level), issue.id, issue.title));
+        let mut meta = vec![format!("**State:** {}", issue.state)];
+        if let Some(reason) = issue.close_reason {
+            meta[0].push_str(&format!(" ({})", reason));
+        }
+        if !issue.labels.is_empty() {
+            meta.push(format!("**Labels:** {}", issue.labels.join(", ")));
+        }
+        if let Some(milestone) = crate::milestones::effective(issue, &by_id) {
+            meta.push(format!("**Milestone:** {}", milestone));
+        }
+        if !issue.assignees.is_empty() {
+            let names: Vec<String> = issue.assignees.iter().map(|a| format!("@{}", a)).collect();
+            meta.push(format!("**Assignees:** {}", names.join(", ")));
+        }
+        if let Some(priority) = issue.priority {
+            meta.push(format!("**Priority:** p{}", priority));
+        }
+        if let Some(due) = issue.due {
+            meta.push(format!("**Due:** {}", due));
+        }
+        out.push_str(&meta.join(" · "));
+        out.push('\n');
+        let content = nest_headings(issue.content.trim_end(), level);
+        if !content.is_empty() {
+            out.push_str(&format!("\n{}\n", content));
+        }
+        if comments && !issue.comments.is_empty() {
+            out.push_str("\n**Comments:**\n\n");
+            for (n, entry) in issue.comments.iter().enumerate() {
+                let (kind, text) = crate::render::comment_kind(entry);
+                let time = issue.comment_time(n).map(|at| format!(" {}", at.format("%Y-%m-%d %H:%M UTC")));
+                out.push_str(&format!("- *{}*{}: {}\n", kind, time.unwrap_or_default(), text));
+            }
+        }
+    }
+    out
+}
+
+/// Push content headings below an issue heading at `level`, leaving code blocks alone
+fn nest_headings(content: &str, level: usize) -> String {
+    let mut in_code = false;
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+            }
+            let hashes = line.len() - line.trim_start_matches('#').len();
+            if in_code || hashes == 0 || !line[hashes..].starts_with(' ') {
+                return line.to_string();
+            }
+            format!("{}{}", "#".repeat((hashes + level).min(6)), &line[hashes..])
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Escape a TEXT value: backslashes, separators and newlines
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Split a content line into 75-octet pieces, continuing each with a leading space
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_ics_event_for_due_issue() {
+        let issue = Issue {
+            id: "001".to_string(),
+            title: "Ship it, finally".to_string(),
+            due: NaiveDate::from_ymd_opt(2026, 3, 31),
+            ..Default::default()
+        };
+        let undated = Issue { id: "002".to_string(), ..Default::default() };
+        let out = ics(&[&issue, &undated], DateTime::UNIX_EPOCH);
+        assert!(out.contains("DTSTART;VALUE=DATE:20260331\r\n"));
+        assert!(out.contains("DTEND;VALUE=DATE:20260401\r\n"));
+        assert!(out.contains("SUMMARY:001 | Ship it\\, finally\r\n"));
+        assert!(!out.contains("UID:002@git-issue"));
+    }
+
+    #[test]
+    fn test_markdown_document() {
+        let issue = |id: &str, title: &str, state: &str, content: &str| Issue {
+            id: id.to_string(),
+            title: title.to_string(),
+            state: state.into(),
+            content: content.to_string(),
+            ..Default::default()
+        };
+        let issues = vec![
+            issue("001", "Epic", "open", "## Goal\nShip"),
+            issue("001-001", "Docs", "closed", "```\n# not a heading\n```"),
+            issue("002", "Other", "open", ""),
+        ];
+        let workflow = Workflow::default();
+        assert_eq!(
+            markdown(&issues, Some("001"), &workflow, false),
+            "# 001 Epic\n\n- [ ] 001 Epic\n  - [x] 001-001 Docs\n\n\
+## 001 Epic\n\n**State:** open\n\n#### Goal\nShip\n\n\
+### 001-001 Docs\n\n**State:** closed\n\n```\n# not a heading\n```\n"
+        );
+        assert!(markdown(&issues, None, &workflow, false).starts_with("# Issues\n\n- [ ] 001 Epic\n  - [x] 001-001 Docs\n- [ ] 002 Other\n"));
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let folded = fold(&"x".repeat(100));
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines[0].len(), 75);
+        assert_eq!(lines[1], format!(" {}", "x".repeat(25)));
+    }
+}
@@ -0,0 +1,327 @@
+// GitHub/GitLab issue JSON: `git issue import github` and `git issue export github`
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::config::Workflow;
+use crate::models::{checklist_item, CloseReason, Issue};
+
+/// One issue as exported by `gh issue list --json`, the GitHub REST API or GitLab
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Record {
+    number: Option<u64>,
+    /// GitLab's per-project issue number
+    iid: Option<u64>,
+    title: String,
+    #[serde(default, alias = "description")]
+    body: Option<String>,
+    state: String,
+    #[serde(default, alias = "state_reason")]
+    state_reason: Option<String>,
+    #[serde(default)]
+    labels: Vec<Named>,
+    #[serde(default)]
+    assignees: Vec<Named>,
+    #[serde(default, alias = "notes")]
+    comments: Comments,
+    /// Set on pull requests listed by the REST API, which are skipped
+    pull_request: Option<serde_json::Value>,
+}
+
+/// A label or user, either a bare string or an object
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Named {
+    Plain(String),
+    Object {
+        login: Option<String>,
+        username: Option<String>,
+        name: Option<String>,
+    },
+}
+
+impl Named {
+    fn text(&self) -> Option<&str> {
+        match self {
+            Named::Plain(text) => Some(text),
+            Named::Object { login, username, name } => login.as_deref().or(username.as_deref()).or(name.as_deref()),
+        }
+    }
+}
+
+/// Comment objects, or just their count as the REST API lists it
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Comments {
+    Items(Vec<CommentRecord>),
+    Count(#[allow(dead_code)] u64),
+}
+
+impl Default for Comments {
+    fn default() -> Self {
+        Comments::Items(Vec::new())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentRecord {
+    #[serde(alias = "user")]
+    author: Option<Named>,
+    #[serde(default)]
+    body: String,
+    #[serde(alias = "created_at")]
+    created_at: Option<DateTime<Utc>>,
+}
+
+/// An issue ready to save, with no ID yet
+pub struct Imported {
+    pub issue: Issue,
+    /// Position of the parent among the imported issues, which always comes earlier
+    pub parent: Option<usize>,
+}
+
+/// Map an export onto issues, parents first
+///
+/// A task-list line such as `- [ ] #12` makes issue 12 a sub-issue, and the line is dropped from the
+/// parent's content since the hierarchy now records it.
+pub fn parse(data: &str, workflow: &Workflow) -> Result<Vec<Imported>> {
+    let records: Vec<Record> = serde_json::from_str(data).context("expected a JSON array of issues")?;
+    let records: Vec<Record> = records.into_iter().filter(|r| r.pull_request.is_none()).collect();
+
+    let mut by_number: HashMap<u64, usize> = HashMap::new();
+    for (n, record) in records.iter().enumerate() {
+        let Some(number) = record.number.or(record.iid) else {
+            bail!("issue '{}' has no number", record.title);
+        };
+        if by_number.insert(number, n).is_some() {
+            bail!("issue #{} appears twice", number);
+        }
+    }
+
+    // Claim sub-issues from task lists; the first parent wins and cycles are ignored
+    let mut parent: Vec<Option<usize>> = vec![None; records.len()];
+    let mut dropped: Vec<HashSet<usize>> = vec![HashSet::new(); records.len()];
+    for (n, record) in records.iter().enumerate() {
+        for (line_no, line) in record.body.as_deref().unwrap_or_default().lines().enumerate() {
+            let Some(child) = task_reference(line).and_then(|number| by_number.get(&number).copied()) else {
+                continue;
+            };
+            let mut ancestor = Some(n);
+            while let Some(a) = ancestor.filter(|&a| a != child) {
+                ancestor = parent[a];
+            }
+            if child == n || parent[child].is_some() || ancestor.is_some() {
+                continue;
+            }
+            parent[child] = Some(n);
+            dropped[n].insert(line_no);
+        }
+    }
+
+    let mut order = Vec::new();
+    let mut roots: Vec<usize> = (0..records.len()).filter(|&n| parent[n].is_none()).collect();
+    roots.sort_by_key(|&n| records[n].number.or(records[n].iid));
+    for root in roots {
+        push_subtree(root, &records, &parent, &mut order);
+    }
+    let position: HashMap<usize, usize> = order.iter().enumerate().map(|(pos, &n)| (n, pos)).collect();
+    order
+        .iter()
+        .map(|&n| {
+            let issue = issue(&records[n], &dropped[n], workflow)
+                .with_context(|| format!("issue #{}", records[n].number.or(records[n].iid).unwrap_or_default()))?;
+            Ok(Imported { issue, parent: parent[n].map(|p| position[&p]) })
+        })
+        .collect()
+}
+
+fn push_subtree(n: usize, records: &[Record], parent: &[Option<usize>], order: &mut Vec<usize>) {
+    order.push(n);
+    let mut children: Vec<usize> = (0..records.len()).filter(|&c| parent[c] == Some(n)).collect();
+    children.sort_by_key(|&c| records[c].number.or(records[c].iid));
+    for child in children {
+        push_subtree(child, records, parent, order);
+    }
+}
+
+/// Issue number a task-list line points at, as in `- [x] #12`
+fn task_reference(line: &str) -> Option<u64> {
+    let (_, text, _) = checklist_item(line)?;
+    let digits: String = text.strip_prefix('#')?.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+fn issue(record: &Record, dropped: &HashSet<usize>, workflow: &Workflow) -> Result<Issue> {
+    let (tracker, number) = match (record.number, record.iid) {
+        (_, Some(iid)) => ("gitlab", iid),
+        (number, None) => ("github", number.unwrap_or_default()),
+    };
+    let (state, close_reason) = match record.state.to_lowercase().as_str() {
+        "open" | "opened" => (workflow.initial_state(), None),
+        "closed" => {
+            let wontfix = record.state_reason.as_deref().is_some_and(|r| r.eq_ignore_ascii_case("not_planned"));
+            (workflow.close_state(), wontfix.then_some(CloseReason::Wontfix))
+        }
+        other => bail!("unknown state '{}' (expected open or closed)", other),
+    };
+    let body = record.body.as_deref().unwrap_or_default();
+    let content: Vec<&str> = body.lines().enumerate().filter(|(n, _)| !dropped.contains(n)).map(|(_, l)| l).collect();
+    let mut issue = Issue {
+        title: record.title.clone(),
+        content: content.join("\n").trim().to_string(),
+        labels: record.labels.iter().filter_map(Named::text).map(String::from).collect(),
+        assignees: record.assignees.iter().filter_map(Named::text).map(String::from).collect(),
+        state,
+        close_reason,
+        reference: Some(format!("{}#{}", tracker, number)),
+        ..Default::default()
+    };
+    if let Comments::Items(comments) = &record.comments {
+        for comment in comments {
+            let entry = match comment.author.as_ref().and_then(Named::text) {
+                Some(author) => format!("+++ @{}: {}", author, comment.body.trim()),
+                None => format!("+++ {}", comment.body.trim()),
+            };
+            issue.comment_times.resize(issue.comments.len(), None);
+            issue.comment_times.push(comment.created_at);
+            issue.comments.push(entry);
+        }
+        if issue.comment_times.iter().all(Option::is_none) {
+            issue.comment_times.clear();
+        }
+    }
+    Ok(issue)
+}
+
+/// Request body for creating an issue through the API
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Payload {
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+}
+
+/// Payload for an issue, listing its direct sub-issues as a task list
+///
+/// Sub-issues already published are linked by number; the rest are named with their local ID.
+pub fn payload(issue: &Issue, issues: &[Issue], workflow: &Workflow, numbers: &HashMap<String, u64>) -> Payload {
+    let mut body = issue.content.trim_end().to_string();
+    let children: Vec<&Issue> = issues.iter().filter(|i| i.parent_id() == Some(issue.id.as_str())).collect();
+    if !children.is_empty() {
+        if !body.is_empty() {
+            body.push_str("\n\n");
+        }
+        body.push_str("### Sub-issues\n");
+        for child in children {
+            let mark = if workflow.is_terminal(&child.state) { "x" } else { " " };
+            match numbers.get(&child.id) {
+                Some(number) => body.push_str(&format!("\n- [{}] #{}", mark, number)),
+                None => body.push_str(&format!("\n- [{}] {} ({})", mark, child.title, child.id)),
+            }
+        }
+    }
+    Payload { title: issue.title.clone(), body, labels: issue.labels.clone() }
+}
+
+/// One exported issue in `mapping.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Mapping {
+    pub id: String,
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Created {
+    number: u64,
+    html_url: Option<String>,
+}
+
+/// Create an issue at `{base}/issues`, returning its number and web address
+pub fn post(base: &str, token: Option<&str>, payload: &Payload) -> Result<(u64, Option<String>)> {
+    let url = format!("{}/issues", base.trim_end_matches('/'));
+    let mut request = ureq::post(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", concat!("git-issue/", env!("CARGO_PKG_VERSION")));
+    if let Some(token) = token {
+        request = request.set("Authorization", &format!("Bearer {}", token));
+    }
+    let response = match request.send_json(payload) {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, response)) => {
+            bail!("POST {} failed with {}: {}", url, code, response.into_string().unwrap_or_default().trim())
+        }
+        Err(e) => bail!("POST {} failed: {}", url, e),
+    };
+    let created: Created = response.into_json().with_context(|| format!("unexpected reply from {}", url))?;
+    Ok((created.number, created.html_url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"[
+        {"number": 3, "title": "Child", "body": "Do it", "state": "CLOSED", "stateReason": "NOT_PLANNED",
+         "labels": [], "comments": []},
+        {"number": 1, "title": "Epic", "body": "Intro\n- [ ] #3\n- [ ] #9\n- [x] plain", "state": "OPEN",
+         "labels": [{"name": "feature", "color": "fff"}], "assignees": [{"login": "ann", "name": "Ann"}],
+         "comments": [{"author": {"login": "bob"}, "body": "Looks good", "createdAt": "2026-01-02T03:04:05Z"}]},
+        {"iid": 7, "title": "Lab", "description": "From GitLab", "state": "opened", "labels": ["ops"]}
+    ]"#;
+
+    #[test]
+    fn test_parse_hierarchy_and_fields() {
+        let workflow = Workflow::default();
+        let imported = parse(EXPORT, &workflow).unwrap();
+        let titles: Vec<&str> = imported.iter().map(|i| i.issue.title.as_str()).collect();
+        assert_eq!(titles, vec!["Epic", "Child", "Lab"]);
+        assert_eq!(imported[1].parent, Some(0));
+        assert_eq!(imported[2].parent, None);
+
+        let epic = &imported[0].issue;
+        assert_eq!(epic.content, "Intro\n- [ ] #9\n- [x] plain");
+        assert_eq!(epic.labels, vec!["feature"]);
+        assert_eq!(epic.assignees, vec!["ann"]);
+        assert_eq!(epic.comments, vec!["+++ @bob: Looks good"]);
+        assert_eq!(epic.comment_time(0), "2026-01-02T03:04:05Z".parse().ok());
+        assert_eq!(epic.reference.as_deref(), Some("github#1"));
+
+        let child = &imported[1].issue;
+        assert_eq!(child.state, workflow.close_state());
+        assert_eq!(child.close_reason, Some(CloseReason::Wontfix));
+        assert_eq!(imported[2].issue.reference.as_deref(), Some("gitlab#7"));
+    }
+
+    #[test]
+    fn test_task_list_cycles_ignored() {
+        let data = r#"[{"number": 1, "title": "A", "body": "- [ ] #2", "state": "open"},
+                       {"number": 2, "title": "B", "body": "- [ ] #1", "state": "open"}]"#;
+        let imported = parse(data, &Workflow::default()).unwrap();
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[1].parent, Some(0));
+        assert_eq!(imported[1].issue.content, "- [ ] #1");
+    }
+
+    #[test]
+    fn test_payload_task_list() {
+        let workflow = Workflow::default();
+        let issue = |id: &str, title: &str, closed: bool| Issue {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: format!("{} body\n", title),
+            state: if closed { workflow.close_state() } else { workflow.initial_state() },
+            ..Default::default()
+        };
+        let issues = vec![issue("001", "Epic", false), issue("001-001", "API", false), issue("001-002", "UI", true)];
+        let numbers = HashMap::from([("001-001".to_string(), 42)]);
+        let payload = payload(&issues[0], &issues, &workflow, &numbers);
+        assert_eq!(payload.body, "Epic body\n\n### Sub-issues\n\n- [ ] #42\n- [x] UI (001-002)");
+    }
+}
@@ -0,0 +1,141 @@
+// Label registry stored in `.issues/labels.yaml`
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use crate::config::LabelRules;
+use crate::LabelFilter;
+use crate::storage::STORAGE_DIR;
+
+pub const LABELS_FILE: &str = "labels.yaml";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LabelDef {
+    pub name: String,
+    /// Hex color, e.g. `#d73a4a`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+fn path() -> PathBuf {
+    PathBuf::from(STORAGE_DIR).join(LABELS_FILE)
+}
+
+/// Load all registered labels, in file order
+pub fn load() -> Result<Vec<LabelDef>> {
+    let path = path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).context("Failed to read labels")?;
+    let labels: Option<Vec<LabelDef>> = serde_yaml::from_str(&data).context("Failed to parse labels")?;
+    Ok(labels.unwrap_or_default())
+}
+
+pub fn save(labels: &[LabelDef]) -> Result<()> {
+    fs::write(path(), serde_yaml::to_string(labels)?).context("Failed to save labels")
+}
+
+/// Scope of a scoped label: `type` for `type::bug`
+pub fn scope(label: &str) -> Option<&str> {
+    label.rsplit_once("::").map(|(scope, _)| scope)
+}
+
+/// Reject names that could not be given to `--label`
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.contains(',') || name.ends_with("::") || name.ends_with("::*") {
+        bail!("invalid label name '{}'", name);
+    }
+    Ok(())
+}
+
+/// Reject colors other than `#rrggbb`
+pub fn validate_color(color: &str) -> Result<()> {
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("invalid color '{}' (expected #rrggbb)", color);
+    }
+    Ok(())
+}
+
+/// Reject two labels from the same scope, e.g. `type::bug` and `type::feature`
+pub fn check_scopes(labels: &[String], rules: &LabelRules) -> Result<()> {
+    for (i, a) in labels.iter().enumerate() {
+        for b in &labels[i + 1..] {
+            if let (Some(sa), Some(sb)) = (scope(a), scope(b)) {
+                if rules.matches(sa, sb) && !rules.matches(a, b) {
+                    bail!("labels '{}' and '{}' share the scope '{}'", a, b, sa);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether an issue's labels pass `--label` (all), `--any-label`, `--no-label` and `--unlabeled`
+pub fn filter_matches(filter: &LabelFilter, labels: &[String], rules: &LabelRules) -> bool {
+    let has = |f: &String| labels.iter().any(|l| rules.filter_matches(f, l));
+    filter.label.iter().all(has)
+        && (filter.any_label.is_empty() || filter.any_label.iter().any(has))
+        && !filter.no_label.iter().any(has)
+        && (!filter.unlabeled || labels.is_empty())
+}
+
+/// Validate the labels of a new issue: the allowed list, strict mode and label scopes
+pub fn check(labels: &[String], rules: &LabelRules) -> Result<()> {
+    rules.check(labels)?;
+    if rules.strict {
+        let known = load()?;
+        for label in labels {
+            if !known.iter().any(|def| rules.matches(&def.name, label)) {
+                bail!("unknown label '{}' (strict mode: define it with `git issue label create`)", label);
+            }
+        }
+    }
+    check_scopes(labels, rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_labels() {
+        let rules = LabelRules::default();
+        assert_eq!(scope("type::bug"), Some("type"));
+        assert_eq!(scope("bug"), None);
+        let labels = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert!(check_scopes(&labels(&["type::bug", "prio::high", "ui"]), &rules).is_ok());
+        assert!(check_scopes(&labels(&["type::bug", "Type::feature"]), &rules).is_err());
+    }
+
+    #[test]
+    fn test_label_filter() {
+        let rules = LabelRules::default();
+        let labels = vec!["bug".to_string(), "prio::p1".to_string()];
+        let filter = |label: &[&str], any: &[&str], no: &[&str]| LabelFilter {
+            label: label.iter().map(|s| s.to_string()).collect(),
+            any_label: any.iter().map(|s| s.to_string()).collect(),
+            no_label: no.iter().map(|s| s.to_string()).collect(),
+            unlabeled: false,
+        };
+        assert!(filter_matches(&filter(&["bug", "prio::*"], &[], &[]), &labels, &rules));
+        assert!(!filter_matches(&filter(&["bug", "backend"], &[], &[]), &labels, &rules));
+        assert!(filter_matches(&filter(&[], &["backend", "BUG"], &[]), &labels, &rules));
+        assert!(!filter_matches(&filter(&[], &[], &["wontfix", "bug"]), &labels, &rules));
+        let unlabeled = LabelFilter { unlabeled: true, ..Default::default() };
+        assert!(!filter_matches(&unlabeled, &labels, &rules));
+        assert!(filter_matches(&unlabeled, &[], &rules));
+    }
+
+    #[test]
+    fn test_validate_color() {
+        assert!(validate_color("#d73a4a").is_ok());
+        assert!(validate_color("d73a4a").is_err());
+        assert!(validate_color("#xyzxyz").is_err());
+    }
+}
@@ -1,11 +1,27 @@
+pub mod output;
 pub mod models;
 pub mod storage;
 pub mod commands;
+pub mod config;
 pub mod logging;
+pub mod migrate;
+pub mod milestones;
+pub mod export;
+pub mod templates;
+pub mod labels;
+pub mod board;
+pub mod tui;
+pub mod render;
+pub mod site;
+pub mod spreadsheet;
+pub mod github;
+pub mod api;
+pub mod mcp;
+pub mod serve;
 
-use clap::Args;
+use clap::{Args, Subcommand};
 
-#[derive(Args, Debug, Clone)]
+#[derive(Args, Debug, Clone, Default, serde::Deserialize, schemars::JsonSchema)]
 pub struct CreateArgs {
     /// Parent issue ID (for sub-issue)
     #[arg(short = 'p', long)]
@@ -13,28 +29,190 @@ pub struct CreateArgs {
     /// Title of the issue
     #[arg(short = 't', long)]
     pub title: String,
-    /// Content/body of the issue
-    #[arg(short = 'c', long)]
+    /// Content/body of the issue [default: the rendered template]
+    #[arg(short = 'c', long, default_value = "")]
+    #[serde(default)]
     pub content: String,
+    /// Template from `.issues/templates/`, or none [default: the template for one of the labels]
+    #[arg(long)]
+    pub template: Option<String>,
     /// Comma-separated labels
     #[arg(long, value_delimiter = ',')]
     pub label: Option<Vec<String>>,
+    /// Close this issue automatically once its last sub-issue closes [default: workflow.auto_close_parents]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub auto_close: Option<bool>,
+    /// Milestone from `.issues/milestones.yaml`
+    #[arg(long)]
+    pub milestone: Option<String>,
+    /// Due date, YYYY-MM-DD
+    #[arg(long)]
+    pub due: Option<chrono::NaiveDate>,
+    /// Planned start date, YYYY-MM-DD
+    #[arg(long)]
+    pub start: Option<chrono::NaiveDate>,
+    /// Expected effort, in hours or points per `estimate.unit`
+    #[arg(long)]
+    pub estimate: Option<String>,
+    /// Custom field value, KEY=VALUE (repeatable)
+    #[arg(long, value_name = "KEY=VALUE")]
+    #[serde(default)]
+    pub field: Vec<String>,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct EditArgs {
+    /// Issue ID
+    pub id: String,
+    /// New title
+    #[arg(short = 't', long)]
+    pub title: Option<String>,
+    /// New content/body
+    #[arg(short = 'c', long)]
+    pub content: Option<String>,
+    /// Due date, YYYY-MM-DD, or none to clear
+    #[arg(long)]
+    pub due: Option<String>,
+    /// Planned start date, YYYY-MM-DD, or none to clear
+    #[arg(long)]
+    pub start: Option<String>,
+    /// Set a custom field, KEY=VALUE, or KEY= to clear it (repeatable)
+    #[arg(long, value_name = "KEY=VALUE")]
+    #[serde(default)]
+    pub field: Vec<String>,
+    /// Comma-separated labels to add
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub add_label: Vec<String>,
+    /// Comma-separated labels to remove
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub remove_label: Vec<String>,
 }
 
 #[derive(Args, Debug, Clone)]
 pub struct LsArgs {
-    /// Filter by state: open, closed, or all
-    #[arg(long, default_value = "open")]
+    #[command(flatten)]
+    pub filter: IssueFilter,
+    /// Sort by: id, priority, rank or a custom field [default: id, or ls.sort]
+    #[arg(long)]
+    pub sort: Option<String>,
+    /// Order: asc or desc [default: asc, or ls.order]
+    #[arg(long)]
+    pub order: Option<String>,
+    /// Show the hierarchy as a tree with sub-issue progress; closed subtrees are collapsed
+    #[arg(long)]
+    pub tree: bool,
+    /// Levels of the tree to show, counting the top level as 1
+    #[arg(long, requires = "tree")]
+    pub depth: Option<usize>,
+    /// Output format [default: text, or output.format]
+    #[arg(long, value_enum)]
+    pub format: Option<config::Format>,
+}
+
+/// Issue selection shared by `ls` and `board`
+#[derive(Args, Debug, Clone, Default)]
+pub struct IssueFilter {
+    /// Filter by state: any workflow state, open, closed, or all [default: open, or ls.state]
+    #[arg(long)]
+    pub state: Option<String>,
+    #[command(flatten)]
+    pub labels: LabelFilter,
+    /// Only issues assigned to this person (`@me` for yourself)
+    #[arg(long, conflicts_with = "unassigned")]
+    pub assignee: Option<String>,
+    /// Only issues without assignees
+    #[arg(long)]
+    pub unassigned: bool,
+    /// Only issues in this milestone, including inherited from a parent
+    #[arg(long)]
+    pub milestone: Option<String>,
+    /// Only issues whose custom field has this value, KEY=VALUE (repeatable)
+    #[arg(long, value_name = "KEY=VALUE")]
+    pub field: Vec<String>,
+    /// Only this issue and its sub-issues
+    #[arg(long)]
+    pub root: Option<String>,
+    /// Only open issues past their due date
+    #[arg(long)]
+    pub overdue: bool,
+    /// Only open issues due within this long, e.g. 7d, including overdue ones
+    #[arg(long)]
+    pub due_within: Option<String>,
+    /// Only the issue imported with this reference, e.g. github#12
+    #[arg(long)]
+    pub reference: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct BoardArgs {
+    /// Group columns by: state, assignee, milestone, priority, or a custom field
+    #[arg(long, default_value = "state")]
+    pub by: String,
+    #[command(flatten)]
+    pub filter: IssueFilter,
+    /// Board width in columns [default: the terminal width]
+    #[arg(long)]
+    pub width: Option<usize>,
+}
+
+/// Label matching shared by `ls`, `search` and `export`; `scope::*` matches a whole scope
+#[derive(Args, Debug, Clone, Default)]
+pub struct LabelFilter {
+    /// Only issues with this label; repeat to require all of them [default: ls.label]
+    #[arg(long)]
+    pub label: Vec<String>,
+    /// Only issues with at least one of these labels (repeatable)
+    #[arg(long)]
+    pub any_label: Vec<String>,
+    /// Leave out issues with this label (repeatable)
+    #[arg(long)]
+    pub no_label: Vec<String>,
+    /// Only issues without any labels
+    #[arg(long, conflicts_with_all = ["label", "any_label"])]
+    pub unlabeled: bool,
+}
+
+impl LabelFilter {
+    pub fn is_empty(&self) -> bool {
+        self.label.is_empty() && self.any_label.is_empty() && self.no_label.is_empty() && !self.unlabeled
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SearchArgs {
+    /// Text to find in titles, content and comments, ignoring case
+    pub query: String,
+    /// Filter by state: any workflow state, open, closed, or all
+    #[arg(long, default_value = "all")]
     pub state: String,
-    /// Filter by label
+    #[command(flatten)]
+    pub labels: LabelFilter,
+    /// Output format [default: text, or output.format]
+    #[arg(long, value_enum)]
+    pub format: Option<config::Format>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ViewArgs {
+    /// Issue ID
+    pub id: String,
+    /// Output format [default: text, or output.format]
+    #[arg(long, value_enum)]
+    pub format: Option<config::Format>,
+    /// Plain text without colors (also set by a non-empty NO_COLOR)
+    #[arg(long)]
+    pub no_color: bool,
+    /// Print long issues directly instead of through $PAGER
     #[arg(long)]
-    pub label: Option<String>,
-    /// Sort by: id
-    #[arg(long, default_value = "id")]
-    pub sort: String,
-    /// Order: asc or desc
-    #[arg(long, default_value = "asc")]
-    pub order: String,
+    pub no_pager: bool,
+    /// Stored text as is: raw markdown and `+++`/`>>>`/`<<<` comment markers [default when piped]
+    #[arg(long, conflicts_with = "render")]
+    pub raw: bool,
+    /// Render markdown and labelled comments even when stdout is not a terminal
+    #[arg(long)]
+    pub render: bool,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -53,6 +231,146 @@ pub struct CloseArgs {
     /// Message
     #[arg(short = 'm', long)]
     pub message: String,
+    /// Why the issue is closed [default: completed]
+    #[arg(long, value_enum)]
+    pub reason: Option<models::CloseReason>,
+    /// Original issue, for duplicate closes
+    #[arg(long)]
+    pub duplicate_of: Option<String>,
+    /// Also close every open descendant, deepest first
+    #[arg(short = 'r', long, conflicts_with = "duplicate_of")]
+    pub recursive: bool,
+    /// Confirm a recursive close
+    #[arg(short = 'y', long, requires = "recursive")]
+    pub yes: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ReopenArgs {
+    /// Issue ID
+    pub id: String,
+    /// Message
+    #[arg(short = 'm', long)]
+    pub message: String,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StatusArgs {
+    /// Issue ID
+    pub id: String,
+    /// Target workflow state
+    pub state: String,
+    /// Message
+    #[arg(short = 'm', long)]
+    pub message: Option<String>,
+    /// Close reason, when moving to a terminal state
+    #[arg(long, value_enum)]
+    pub reason: Option<models::CloseReason>,
+    /// Original issue, for duplicate closes
+    #[arg(long)]
+    pub duplicate_of: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StatsArgs {
+    /// Limit statistics to the sub-issues of this issue
+    pub id: Option<String>,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct PrioritizeArgs {
+    /// Issue ID
+    pub id: String,
+    /// Priority: p0 (most urgent) to p3, a number, or none to clear
+    pub priority: String,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct ReorderArgs {
+    /// Issue ID
+    pub id: String,
+    /// Sibling to place the issue before
+    #[arg(long, conflicts_with = "after", required_unless_present = "after")]
+    pub before: Option<String>,
+    /// Sibling to place the issue after
+    #[arg(long)]
+    pub after: Option<String>,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct AssignArgs {
+    /// Issue ID
+    pub id: String,
+    /// Assignees; `@me` stands for the current author
+    #[arg(required = true)]
+    pub who: Vec<String>,
+    /// Remove these assignees instead of adding them
+    #[arg(long)]
+    #[serde(default)]
+    pub remove: bool,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct ClaimArgs {
+    /// Issue to claim or renew [default: the next ready, unclaimed issue]
+    pub id: Option<String>,
+    /// Claim on behalf of someone else [default: @me]
+    #[arg(long = "as")]
+    pub who: Option<String>,
+    /// Lease length, e.g. 30m or 2h [default: claim.lease]
+    #[arg(long)]
+    pub lease: Option<String>,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct CheckArgs {
+    /// Issue ID
+    pub id: String,
+    /// Checklist item numbers, counting `- [ ]` lines in the content from 1
+    #[arg(required = true)]
+    pub items: Vec<usize>,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct EstimateArgs {
+    /// Issue ID
+    pub id: String,
+    /// Hours (e.g. 4 or 1h30m) or points (e.g. 3), per `estimate.unit`, or none to clear
+    pub estimate: String,
+}
+
+#[derive(Args, Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct TrackArgs {
+    /// Issue ID
+    pub id: String,
+    /// Time spent, e.g. 45m or 1h30m
+    pub spent: String,
+    /// What the time was spent on
+    #[arg(short = 'm', long, default_value = "")]
+    #[serde(default)]
+    pub message: String,
+    /// Log the work for someone else [default: @me]
+    #[arg(long = "as")]
+    pub who: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+    #[command(subcommand)]
+    pub kind: ReportKind,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ReportKind {
+    /// Time logged per label and per assignee
+    Time {
+        /// Only work logged on or after this date, YYYY-MM-DD
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+        /// Only work logged on or before this date, YYYY-MM-DD
+        #[arg(long)]
+        until: Option<chrono::NaiveDate>,
+    },
 }
 
 #[derive(Args, Debug, Clone)]
@@ -71,3 +389,184 @@ pub struct PlanArgs {
     #[arg(short = 'j', long)]
     pub json: Option<String>,
 }
+
+#[derive(Args, Debug, Clone)]
+pub struct MigrateArgs {
+    /// Show the changes as a diff without rewriting any files
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Print the value of a dotted key, e.g. `ls.state`
+    Get {
+        key: String,
+        /// Read only the user-level config
+        #[arg(long)]
+        global: bool,
+    },
+    /// Set a dotted key in `.issues/config.yaml`
+    Set {
+        key: String,
+        value: String,
+        /// Write the user-level config instead
+        #[arg(long)]
+        global: bool,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct MilestoneArgs {
+    #[command(subcommand)]
+    pub action: MilestoneAction,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum MilestoneAction {
+    /// List milestones with progress
+    Ls,
+    /// Show a milestone and its issues
+    Show { name: String },
+    /// Define a new milestone
+    Create {
+        name: String,
+        /// What the milestone delivers
+        #[arg(short = 'd', long, default_value = "")]
+        description: String,
+        /// Due date, YYYY-MM-DD
+        #[arg(long)]
+        due: Option<chrono::NaiveDate>,
+    },
+    /// Put an issue in a milestone, or `none` to remove it
+    Set { id: String, name: String },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub format: ExportFormat,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ExportFormat {
+    /// iCalendar feed of open issues' due dates
+    Ics {
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long)]
+        out: Option<std::path::PathBuf>,
+        #[command(flatten)]
+        labels: LabelFilter,
+    },
+    /// Markdown document: a checklist of the hierarchy, then each issue's content
+    Md {
+        /// Only this issue and its sub-issues
+        id: Option<String>,
+        /// Include each issue's comments
+        #[arg(long)]
+        comments: bool,
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long)]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Static HTML site: a filterable index plus issue, label and milestone pages
+    Html {
+        /// Directory to write the site into
+        #[arg(short = 'o', long)]
+        out: std::path::PathBuf,
+    },
+    /// GitHub issue request bodies plus `mapping.json`, optionally published with `--post`
+    Github {
+        /// Only this issue and its sub-issues
+        id: Option<String>,
+        /// Directory to write the request bodies into
+        #[arg(short = 'o', long)]
+        out: std::path::PathBuf,
+        /// Create the issues through the API, authenticated with `GITHUB_TOKEN` if set
+        #[arg(long)]
+        post: bool,
+        /// Repository API base for `--post` [default: github.url]
+        #[arg(long, requires = "post")]
+        url: Option<String>,
+    },
+    /// Spreadsheet rows, one per issue
+    Csv {
+        /// Comma-separated columns: id, parent, title, state, labels, comments, content, assignees,
+        /// milestone, priority, due, start, estimate or a custom field [default: id to comments plus custom fields]
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<String>,
+        /// Separate cells with tabs
+        #[arg(long)]
+        tsv: bool,
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long)]
+        out: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub format: ImportFormat,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportFormat {
+    /// Create or update issues from spreadsheet rows; rows with an unknown or empty id are created
+    Csv {
+        /// File with a header row, as written by `export csv`
+        file: std::path::PathBuf,
+        /// Separate cells with tabs [default: on for .tsv files]
+        #[arg(long)]
+        tsv: bool,
+        /// Check every row and show what would change, without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Issues from a GitHub (`gh issue list --json`, REST API) or GitLab JSON export, with comments and
+    /// task-list sub-issues; the original number is kept as the issue's reference, e.g. github#12
+    Github {
+        /// JSON array of issues
+        file: std::path::PathBuf,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct LabelArgs {
+    #[command(subcommand)]
+    pub action: LabelAction,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum LabelAction {
+    /// List labels with how many issues use them
+    Ls,
+    /// Define a label
+    Create {
+        name: String,
+        /// Hex color, e.g. #d73a4a
+        #[arg(long)]
+        color: Option<String>,
+        /// What the label means
+        #[arg(short = 'd', long, default_value = "")]
+        description: String,
+    },
+    /// Rename a label on every issue
+    Rename { from: String, to: String },
+    /// Replace labels with another on every issue
+    Merge {
+        #[arg(required = true)]
+        from: Vec<String>,
+        /// Label that replaces them
+        #[arg(long)]
+        into: String,
+    },
+    /// Remove a label from the registry and every issue
+    Delete { name: String },
+}
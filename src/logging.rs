@@ -4,12 +4,18 @@ use anyhow::{Context, Result};
 use std::fs::{OpenOptions, File};
 use std::io::{Write, BufRead, BufReader};
 use std::path::PathBuf;
+use crate::config::Config;
+use crate::models::{CloseReason, Issue};
 use crate::storage::STORAGE_DIR;
 
 pub const AUDIT_LOG: &str = "audit.log";
 
-/// Append a single-line entry (no timestamp) to `.issues/audit.log`
+/// Append a single-line entry (no timestamp) to `.issues/audit.log`, tagged with the author if known
 pub fn append_log(entry: &str) -> Result<()> {
+    let entry = match Config::load()?.author.identity() {
+        Some(author) => format!("{} author={}", entry, author),
+        None => entry.to_string(),
+    };
     let mut path = PathBuf::from(STORAGE_DIR);
     path.push(AUDIT_LOG);
     let mut file = OpenOptions::new()
@@ -21,6 +27,55 @@ pub fn append_log(entry: &str) -> Result<()> {
     Ok(())
 }
 
+/// Audit entries for parents auto-closed after `trigger` closed
+pub fn log_cascade(trigger: &str, parents: &[String]) -> Result<()> {
+    for id in parents {
+        append_log(&format!("CLOSE id={} msg=all sub-issues closed cascade={}", id, trigger))?;
+    }
+    Ok(())
+}
+
+/// Audit log fields for a close reason, e.g. ` reason=duplicate duplicate_of=002`
+pub fn reason_suffix(reason: Option<CloseReason>, duplicate_of: Option<&str>) -> String {
+    let mut suffix = String::new();
+    if let Some(reason) = reason.or(duplicate_of.map(|_| CloseReason::Duplicate)) {
+        suffix.push_str(&format!(" reason={}", reason));
+    }
+    if let Some(of) = duplicate_of {
+        suffix.push_str(&format!(" duplicate_of={}", of));
+    }
+    suffix
+}
+
+/// Checklist item numbers for `CHECK`/`UNCHECK` entries, e.g. `1,3`
+pub fn join_items(items: &[usize]) -> String {
+    items.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Audit entry for `edit`, listing only what changed
+pub fn edit_entry(args: &crate::EditArgs, issue: &Issue) -> String {
+    let mut entry = format!("EDIT id={}", issue.id);
+    if args.title.is_some() {
+        entry.push_str(&format!(" title={}", issue.title));
+    }
+    if args.content.is_some() {
+        entry.push_str(" content=updated");
+    }
+    if let Some(due) = &args.due {
+        entry.push_str(&format!(" due={}", due));
+    }
+    if let Some(start) = &args.start {
+        entry.push_str(&format!(" start={}", start));
+    }
+    if !args.field.is_empty() {
+        entry.push_str(&format!(" fields={}", args.field.join(",")));
+    }
+    if !args.add_label.is_empty() || !args.remove_label.is_empty() {
+        entry.push_str(&format!(" labels={}", issue.labels.join(",")));
+    }
+    entry
+}
+
 /// Read and print the audit log in descending order, applying `limit` if present
 pub fn show_log(limit: Option<usize>) -> Result<()> {
     let mut path = PathBuf::from(STORAGE_DIR);
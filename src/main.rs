@@ -1,7 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use git_issue::commands;
-use git_issue::logging::{append_log, show_log};
+use git_issue::logging::{append_log, edit_entry, join_items, log_cascade, reason_suffix, show_log};
+use chrono::SecondsFormat;
 use std::fs;
 
 const STORAGE_DIR: &str = ".issues";
@@ -17,53 +18,333 @@ struct Cli {
 enum Commands {
     /// Create a new issue or sub-issue
     Create(git_issue::CreateArgs),
+    /// Change an issue's title, content or dates
+    Edit(git_issue::EditArgs),
     /// List issues
     Ls(git_issue::LsArgs),
+    /// Show issues as a kanban board
+    Board(git_issue::BoardArgs),
+    /// Browse and triage issues in a full-screen interface
+    Tui,
+    /// Serve issue tools to AI agents over the Model Context Protocol (stdio)
+    Mcp,
+    /// Serve a local HTTP JSON API for editors and dashboards
+    Serve {
+        /// Port on 127.0.0.1 to listen on; 0 picks a free one
+        #[arg(long, default_value_t = 7878)]
+        port: u16,
+    },
+    /// Find issues by text in titles, content and comments
+    Search(git_issue::SearchArgs),
     /// View an issue and its details
-    View { id: String },
+    View(git_issue::ViewArgs),
     /// Add a comment
     Comment(git_issue::CommentArgs),
     /// Close an issue
     Close(git_issue::CloseArgs),
     /// Reopen an issue
-    Reopen(git_issue::CloseArgs),
+    Reopen(git_issue::ReopenArgs),
+    /// Move an issue to a workflow state
+    Status(git_issue::StatusArgs),
+    /// Set an issue's priority
+    Prioritize(git_issue::PrioritizeArgs),
+    /// Move an issue before or after a sibling
+    Reorder(git_issue::ReorderArgs),
+    /// Tick checklist items in an issue's content
+    Check(git_issue::CheckArgs),
+    /// Untick checklist items in an issue's content
+    Uncheck(git_issue::CheckArgs),
+    /// Set an issue's estimate
+    Estimate(git_issue::EstimateArgs),
+    /// Log time spent on an issue
+    Track(git_issue::TrackArgs),
+    /// Add or remove assignees
+    Assign(git_issue::AssignArgs),
+    /// Claim the next ready issue, with a lease
+    Claim(git_issue::ClaimArgs),
+    /// Release a claimed issue
+    Release { id: String },
+    /// Manage labels
+    Label(git_issue::LabelArgs),
+    /// Manage milestones
+    Milestone(git_issue::MilestoneArgs),
+    /// Show the next issue to work on
+    Next,
+    /// Show open issues grouped by due date
+    Agenda,
+    /// Export issues, e.g. as an iCalendar feed
+    Export(git_issue::ExportArgs),
+    /// Create or update issues from a file, e.g. CSV rows
+    Import(git_issue::ImportArgs),
+    /// Summarise logged time
+    Report(git_issue::ReportArgs),
+    /// Show completion statistics
+    Stats(git_issue::StatsArgs),
     /// Show write-only audit trail
     Log(git_issue::LogArgs),
     /// Batch create issues and sub-issues from JSON
     Plan(git_issue::PlanArgs),
+    /// Upgrade every issue file to the current schema version
+    Migrate(git_issue::MigrateArgs),
+    /// Get or set configuration values
+    Config(git_issue::ConfigArgs),
 }
 
 
 
 
-fn main() -> Result<()> {
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {:#}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
     let cli = Cli::parse();
     fs::create_dir_all(STORAGE_DIR)?;
 
+    // Writes hold the store lock for the whole command, so they never interleave with the
+    // server's; claim takes it itself, and the long-running commands lock per action
+    let _lock = match &cli.command {
+        Commands::Ls(_)
+        | Commands::Board(_)
+        | Commands::Search(_)
+        | Commands::View(_)
+        | Commands::Next
+        | Commands::Agenda
+        | Commands::Export(_)
+        | Commands::Report(_)
+        | Commands::Stats(_)
+        | Commands::Log(_) => None,
+        Commands::Claim(_) | Commands::Tui | Commands::Mcp | Commands::Serve { .. } => None,
+        _ => Some(git_issue::storage::lock()?),
+    };
+
     match cli.command {
         Commands::Create(args) => {
             let issue = commands::create(args)?;
             append_log(&format!("CREATE id={} title={}", issue.id, issue.title))?;
         }
-        Commands::Ls(args) => commands::list(args)?,
-        Commands::View { id } => commands::view(&id)?,
+        Commands::Edit(args) => {
+            let issue = commands::edit(args.clone())?;
+            append_log(&edit_entry(&args, &issue))?;
+        }
+        Commands::Ls(args) => {
+            commands::list(args)?;
+        }
+        Commands::Board(args) => commands::board(args)?,
+        // Each action logs itself as it happens
+        Commands::Tui => git_issue::tui::run()?,
+        Commands::Mcp => git_issue::mcp::run()?,
+        // Each request logs itself as it is handled
+        Commands::Serve { port } => git_issue::serve::run(port)?,
+        Commands::Search(args) => {
+            commands::search(args)?;
+        }
+        Commands::View(args) => commands::view(args)?,
         Commands::Comment(args) => {
             commands::comment(&args.id, &args.message)?;
             append_log(&format!("COMMENT id={} msg={}", args.id, args.message))?;
         }
+        Commands::Close(args) if args.recursive => {
+            let (closed, parents) =
+                commands::close_recursive(&args.id, &args.message, args.reason, args.yes)?;
+            for id in closed {
+                append_log(&format!(
+                    "CLOSE id={} msg={}{} recursive={}",
+                    id,
+                    args.message,
+                    reason_suffix(args.reason, None),
+                    args.id
+                ))?;
+            }
+            log_cascade(&args.id, &parents)?;
+        }
         Commands::Close(args) => {
-            commands::close(&args.id, &args.message)?;
-            append_log(&format!("CLOSE id={} msg={}", args.id, args.message))?;
+            let parents =
+                commands::close(&args.id, &args.message, args.reason, args.duplicate_of.as_deref())?;
+            append_log(&format!(
+                "CLOSE id={} msg={}{}",
+                args.id,
+                args.message,
+                reason_suffix(args.reason, args.duplicate_of.as_deref())
+            ))?;
+            log_cascade(&args.id, &parents)?;
         }
         Commands::Reopen(args) => {
             commands::reopen(&args.id, &args.message)?;
             append_log(&format!("REOPEN id={} msg={}", args.id, args.message))?;
         }
+        Commands::Status(args) => {
+            let (state, parents) = commands::status(
+                &args.id,
+                &args.state,
+                args.message.as_deref(),
+                args.reason,
+                args.duplicate_of.as_deref(),
+            )?;
+            append_log(&format!(
+                "STATUS id={} state={} msg={}{}",
+                args.id,
+                state,
+                args.message.as_deref().unwrap_or_default(),
+                reason_suffix(args.reason, args.duplicate_of.as_deref())
+            ))?;
+            log_cascade(&args.id, &parents)?;
+        }
+        Commands::Prioritize(args) => {
+            let priority = commands::prioritize(&args.id, &args.priority)?;
+            let priority = priority.map_or("none".to_string(), |p| format!("p{}", p));
+            append_log(&format!("PRIORITIZE id={} priority={}", args.id, priority))?;
+        }
+        Commands::Reorder(args) => {
+            let order = commands::reorder(&args.id, args.before.as_deref(), args.after.as_deref())?;
+            append_log(&format!("REORDER id={} order={}", args.id, order.join(",")))?;
+        }
+        Commands::Check(args) => {
+            let (done, total) = commands::check(&args.id, &args.items, true)?;
+            append_log(&format!("CHECK id={} items={} progress={}/{}", args.id, join_items(&args.items), done, total))?;
+        }
+        Commands::Uncheck(args) => {
+            let (done, total) = commands::check(&args.id, &args.items, false)?;
+            append_log(&format!("UNCHECK id={} items={} progress={}/{}", args.id, join_items(&args.items), done, total))?;
+        }
+        Commands::Estimate(args) => {
+            let estimate = commands::estimate(&args.id, &args.estimate)?;
+            let estimate = estimate.map_or("none".to_string(), |e| e.to_string());
+            append_log(&format!("ESTIMATE id={} estimate={}", args.id, estimate))?;
+        }
+        Commands::Track(args) => {
+            let entry = commands::track(&args.id, &args.spent, &args.message, args.who.as_deref())?;
+            append_log(&format!(
+                "TRACK id={} minutes={} by={} msg={}",
+                args.id, entry.minutes, entry.by, entry.message
+            ))?;
+        }
+        Commands::Assign(args) => {
+            let assignees = commands::assign(&args.id, &args.who, args.remove)?;
+            append_log(&format!("ASSIGN id={} assignees={}", args.id, assignees.join(",")))?;
+        }
+        Commands::Claim(args) => {
+            // Log lapsed leases even if the claim itself is refused
+            let mut released = commands::release_expired()?;
+            let outcome = commands::claim(args.id.as_deref(), args.who.as_deref(), args.lease.as_deref());
+            if let Ok(outcome) = &outcome {
+                released.extend(outcome.released.iter().cloned());
+            }
+            for (id, by) in released {
+                append_log(&format!("RELEASE id={} by={} expired=true", id, by))?;
+            }
+            let outcome = outcome?;
+            if let Some(issue) = outcome.claimed {
+                let claim = issue.claim.expect("claimed issue holds a claim");
+                append_log(&format!(
+                    "CLAIM id={} by={} until={}",
+                    issue.id,
+                    claim.by,
+                    claim.until.to_rfc3339_opts(SecondsFormat::Secs, true)
+                ))?;
+            }
+        }
+        Commands::Release { id } => {
+            let by = commands::release(&id)?;
+            append_log(&format!("RELEASE id={} by={}", id, by))?;
+        }
+        Commands::Label(args) => {
+            let count = commands::label(args.clone())?;
+            match args.action {
+                git_issue::LabelAction::Ls => {}
+                git_issue::LabelAction::Create { name, color, .. } => {
+                    append_log(&format!("LABEL create name={} color={}", name, color.unwrap_or_default()))?;
+                }
+                git_issue::LabelAction::Rename { from, to } => {
+                    append_log(&format!("LABEL rename from={} to={} issues={}", from, to, count))?;
+                }
+                git_issue::LabelAction::Merge { from, into } => {
+                    append_log(&format!("LABEL merge from={} into={} issues={}", from.join(","), into, count))?;
+                }
+                git_issue::LabelAction::Delete { name } => {
+                    append_log(&format!("LABEL delete name={} issues={}", name, count))?;
+                }
+            }
+        }
+        Commands::Milestone(args) => {
+            commands::milestone(args.clone())?;
+            match args.action {
+                git_issue::MilestoneAction::Create { name, due, .. } => {
+                    let due = due.map(|d| d.to_string()).unwrap_or_default();
+                    append_log(&format!("MILESTONE name={} due={}", name, due))?;
+                }
+                git_issue::MilestoneAction::Set { id, name } => {
+                    append_log(&format!("MILESTONE id={} name={}", id, name))?;
+                }
+                _ => {}
+            }
+        }
+        Commands::Next => {
+            commands::next()?;
+        }
+        Commands::Agenda => commands::agenda()?,
+        Commands::Report(args) => commands::report(args)?,
+        Commands::Export(args) => commands::export(args)?,
+        Commands::Import(args) => {
+            let (format, file, dry_run) = match &args.format {
+                git_issue::ImportFormat::Csv { file, dry_run, .. } => ("csv", file.clone(), *dry_run),
+                git_issue::ImportFormat::Github { file } => ("github", file.clone(), false),
+            };
+            // Log whatever was written, even if the import stopped part way
+            let mut report = commands::ImportReport::default();
+            let result = commands::import(args, &mut report);
+            if !dry_run {
+                for issue in &report.created {
+                    let mut entry = format!("CREATE id={} title={}", issue.id, issue.title);
+                    if let Some(reference) = &issue.reference {
+                        entry.push_str(&format!(" ref={}", reference));
+                    }
+                    append_log(&entry)?;
+                }
+                for id in &report.updated {
+                    append_log(&format!("EDIT id={} source=import", id))?;
+                }
+                for (id, state, parents) in &report.moved {
+                    append_log(&format!("STATUS id={} state={} msg=imported", id, state))?;
+                    log_cascade(id, parents)?;
+                }
+            }
+            result?;
+            if !dry_run {
+                append_log(&format!(
+                    "IMPORT format={} file={} created={} updated={}",
+                    format,
+                    file.display(),
+                    report.created.len(),
+                    report.updated.len()
+                ))?;
+            }
+        }
+        Commands::Stats(args) => {
+            commands::stats(args.id.as_deref())?;
+        }
         Commands::Log(args) => show_log(args.limit)?,
         Commands::Plan(args) => {
             let (parent_id, parent_title) = commands::plan(args.clone())?;
             append_log(&format!("PLAN parent_id={} parent_title={}", parent_id, parent_title))?;
         }
+        Commands::Migrate(args) => {
+            let dry_run = args.dry_run;
+            let count = commands::migrate(args)?;
+            if !dry_run {
+                append_log(&format!("MIGRATE count={} schema_version={}", count, git_issue::migrate::SCHEMA_VERSION))?;
+            }
+        }
+        Commands::Config(args) => {
+            commands::config(args.clone())?;
+            if let git_issue::ConfigAction::Set { key, value, global } = args.action {
+                let scope = if global { "user" } else { "repo" };
+                append_log(&format!("CONFIG scope={} key={} value={}", scope, key, value))?;
+            }
+        }
     }
 
     Ok(())
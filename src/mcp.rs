@@ -0,0 +1,139 @@
+// Model Context Protocol server over stdio: `git issue mcp`
+
+use anyhow::Result;
+use schemars::{schema_for, JsonSchema};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use crate::api::{call, CloseParams, IdParams, LsParams, MessageParams, NoParams, SearchParams};
+use crate::commands::PlanSpec;
+
+/// Protocol revisions this server speaks, newest last
+const PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+fn tool<T: JsonSchema>(name: &str, description: &str) -> Value {
+    json!({ "name": name, "description": description, "inputSchema": schema_for!(T) })
+}
+
+fn tools() -> Vec<Value> {
+    vec![
+        tool::<crate::CreateArgs>("create", "Create an issue, or a sub-issue with `parent`"),
+        tool::<PlanSpec>("plan", "Create a parent issue and its sub-issues in one go; nothing is created if any is invalid"),
+        tool::<LsParams>("ls", "List issues, open ones by default"),
+        tool::<IdParams>("view", "Show an issue with its content, comments, sub-issue IDs and rolled-up effort"),
+        tool::<MessageParams>("comment", "Add a comment to an issue"),
+        tool::<CloseParams>("close", "Close an issue once its work is done, with `recursive` its open sub-issues too; parents may close with it"),
+        tool::<MessageParams>("reopen", "Reopen a closed issue"),
+        tool::<NoParams>("next", "The next issue to work on: open, with no open sub-issues, most urgent first"),
+        tool::<SearchParams>("search", "Find issues whose title, content or comments contain the query"),
+        tool::<crate::EditArgs>("edit", "Change an issue's title, content, dates, custom fields or labels"),
+        tool::<crate::AssignArgs>("assign", "Add or remove assignees; `@me` stands for the current author"),
+        tool::<crate::ClaimArgs>("claim", "Claim an issue, or the next ready one nobody holds, with a lease"),
+        tool::<IdParams>("release", "Release a claimed issue"),
+        tool::<crate::PrioritizeArgs>("prioritize", "Set an issue's priority: p0 (most urgent) to p3, or none"),
+        tool::<crate::ReorderArgs>("reorder", "Move an issue before or after a sibling"),
+        tool::<crate::CheckArgs>("check", "Tick off checklist items by number"),
+        tool::<crate::CheckArgs>("uncheck", "Untick checklist items by number"),
+        tool::<crate::EstimateArgs>("estimate", "Set or clear an issue's expected effort"),
+        tool::<crate::TrackArgs>("track", "Log time spent on an issue"),
+    ]
+}
+
+/// Answer JSON-RPC messages, one per line, until stdin closes
+pub fn run() -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle(&message),
+            Err(e) => Some(error(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+        if let Some(reply) = reply {
+            writeln!(stdout, "{}", reply)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Reply to a request; notifications get none
+fn handle(message: &Value) -> Option<Value> {
+    if !message.is_object() {
+        return Some(error(Value::Null, INVALID_REQUEST, "expected a request object"));
+    }
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match message.get("method").and_then(Value::as_str).unwrap_or_default() {
+        "initialize" => {
+            let requested = params["protocolVersion"].as_str().unwrap_or_default();
+            let version = PROTOCOL_VERSIONS.iter().find(|v| **v == requested).or(PROTOCOL_VERSIONS.last());
+            json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "git-issue", "version": env!("CARGO_PKG_VERSION") },
+            })
+        }
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools() }),
+        "tools/call" => {
+            let name = params["name"].as_str().unwrap_or_default();
+            if !tools().iter().any(|t| t["name"] == name) {
+                return Some(error(id, INVALID_PARAMS, &format!("unknown tool '{}'", name)));
+            }
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+            let (result, text) = crate::output::capture(|| call(name, arguments));
+            match result {
+                Ok(structured) => json!({
+                    "content": [{ "type": "text", "text": text }],
+                    "structuredContent": structured,
+                    "isError": false,
+                }),
+                Err(e) => json!({
+                    "content": [{ "type": "text", "text": format!("{}error: {:#}", text, e) }],
+                    "isError": true,
+                }),
+            }
+        }
+        method => return Some(error(id, METHOD_NOT_FOUND, &format!("unknown method '{}'", method))),
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_schemas() {
+        let tools = tools();
+        let create = &tools[0]["inputSchema"];
+        assert_eq!(create["type"], "object");
+        assert_eq!(create["required"], json!(["title"]));
+        assert_eq!(create["properties"]["due"]["format"], "date");
+        let plan = &tools[1]["inputSchema"];
+        assert_eq!(plan["required"], json!(["title", "sub_issues"]));
+        assert_eq!(tools[7]["inputSchema"]["type"], "object");
+    }
+
+    #[test]
+    fn test_requests_without_tools() {
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert_eq!(handle(&notification), None);
+        let init = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocolVersion": "2024-11-05" } });
+        assert_eq!(handle(&init).unwrap()["result"]["protocolVersion"], "2024-11-05");
+        let unknown = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" });
+        assert_eq!(handle(&unknown).unwrap()["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
@@ -0,0 +1,110 @@
+// Schema versioning and migrations for issue files
+
+use anyhow::{bail, Result};
+use serde_yaml::{Mapping, Value};
+
+/// Schema version written by `storage::save`
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// Key holding the schema version at the top of every issue file
+pub const VERSION_KEY: &str = "schema_version";
+
+/// Upgrades a raw issue mapping by exactly one version
+type Migration = fn(&mut Mapping) -> Result<()>;
+
+/// Registry of migrations, where `MIGRATIONS[n]` upgrades version `n` to `n + 1`
+const MIGRATIONS: &[Migration] = &[v0_to_v1, v1_to_v2];
+
+/// Files written before versioning have no `schema_version`; the shape is unchanged
+fn v0_to_v1(_issue: &mut Mapping) -> Result<()> {
+    Ok(())
+}
+
+/// `state` changes from the `Open`/`Closed` enum to a lowercase workflow state name
+fn v1_to_v2(issue: &mut Mapping) -> Result<()> {
+    if let Some(Value::String(state)) = issue.get_mut("state") {
+        *state = state.to_lowercase();
+    }
+    Ok(())
+}
+
+/// Read the schema version of a raw issue, treating a missing field as version 0
+pub fn version_of(value: &Value) -> Result<u64> {
+    match value.get(VERSION_KEY) {
+        None => Ok(0),
+        Some(v) => match v.as_u64() {
+            Some(n) => Ok(n),
+            None => bail!("invalid {}: {:?}", VERSION_KEY, v),
+        },
+    }
+}
+
+/// Apply every pending migration to a raw issue, returning the original version
+pub fn upgrade(value: &mut Value) -> Result<u64> {
+    let from = version_of(value)?;
+    if from > SCHEMA_VERSION {
+        bail!(
+            "schema version {} is newer than the supported version {}",
+            from,
+            SCHEMA_VERSION
+        );
+    }
+    let Some(map) = value.as_mapping_mut() else {
+        bail!("issue file is not a mapping");
+    };
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(map)?;
+    }
+    map.remove(VERSION_KEY);
+    Ok(from)
+}
+
+/// Stamp a serialized issue with the current schema version as its first key
+pub fn stamp(value: Value) -> Value {
+    let mut out = Mapping::new();
+    out.insert(VERSION_KEY.into(), SCHEMA_VERSION.into());
+    if let Value::Mapping(map) = value {
+        for (k, v) in map {
+            out.insert(k, v);
+        }
+    }
+    Value::Mapping(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_unversioned() {
+        let mut value: Value = serde_yaml::from_str("id: '001'\ntitle: t\n").unwrap();
+        assert_eq!(upgrade(&mut value).unwrap(), 0);
+        assert!(value.get(VERSION_KEY).is_none());
+        assert_eq!(value.get("id").unwrap().as_str(), Some("001"));
+    }
+
+    #[test]
+    fn test_upgrade_lowercases_state() {
+        let mut value: Value = serde_yaml::from_str("schema_version: 1\nstate: Closed\n").unwrap();
+        assert_eq!(upgrade(&mut value).unwrap(), 1);
+        assert_eq!(value.get("state").unwrap().as_str(), Some("closed"));
+    }
+
+    #[test]
+    fn test_upgrade_rejects_newer_version() {
+        let mut value: Value = serde_yaml::from_str("schema_version: 99\nid: '001'\n").unwrap();
+        assert!(upgrade(&mut value).is_err());
+    }
+
+    #[test]
+    fn test_stamp_puts_version_first() {
+        let value: Value = serde_yaml::from_str("id: '001'\n").unwrap();
+        let yaml = serde_yaml::to_string(&stamp(value)).unwrap();
+        assert!(yaml.starts_with(&format!("schema_version: {}\n", SCHEMA_VERSION)));
+    }
+
+    #[test]
+    fn test_registry_covers_every_version() {
+        assert_eq!(MIGRATIONS.len() as u64, SCHEMA_VERSION);
+    }
+}
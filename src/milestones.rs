@@ -0,0 +1,67 @@
+// Milestone definitions stored in `.issues/milestones.yaml`
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::models::Issue;
+use crate::storage::STORAGE_DIR;
+
+pub const MILESTONES_FILE: &str = "milestones.yaml";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Milestone {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+}
+
+fn path() -> PathBuf {
+    PathBuf::from(STORAGE_DIR).join(MILESTONES_FILE)
+}
+
+/// Load all milestones, in file order
+pub fn load() -> Result<Vec<Milestone>> {
+    let path = path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).context("Failed to read milestones")?;
+    let milestones: Option<Vec<Milestone>> =
+        serde_yaml::from_str(&data).context("Failed to parse milestones")?;
+    Ok(milestones.unwrap_or_default())
+}
+
+pub fn save(milestones: &[Milestone]) -> Result<()> {
+    fs::write(path(), serde_yaml::to_string(milestones)?).context("Failed to save milestones")
+}
+
+/// Look up a milestone by name
+pub fn find(name: &str) -> Result<Milestone> {
+    let milestones = load()?;
+    match milestones.iter().find(|m| m.name == name) {
+        Some(m) => Ok(m.clone()),
+        None => bail!(
+            "unknown milestone '{}' (milestones: {})",
+            name,
+            milestones.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// Milestone of an issue, inherited from the nearest ancestor that has one
+pub fn effective<'a>(issue: &'a Issue, by_id: &HashMap<&str, &'a Issue>) -> Option<&'a str> {
+    let mut current = Some(issue);
+    while let Some(i) = current {
+        if let Some(m) = i.milestone.as_deref() {
+            return Some(m);
+        }
+        current = i.parent_id().and_then(|p| by_id.get(p).copied());
+    }
+    None
+}
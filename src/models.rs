@@ -1,8 +1,11 @@
 // Data structures for issues and state
 
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Issue {
     pub id: String,
     pub title: String,
@@ -10,6 +13,224 @@ pub struct Issue {
     pub labels: Vec<String>,
     pub state: State,
     pub comments: Vec<String>,
+    /// When each comment was added, by position; comments from older versions have none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comment_times: Vec<Option<DateTime<Utc>>>,
+    /// Why the issue was closed; unset means completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub close_reason: Option<CloseReason>,
+    /// Issue this one duplicates, for `duplicate` closes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+    /// Close automatically when the last sub-issue closes; unset follows config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_close: Option<bool>,
+    /// Urgency, 0 being the most urgent (`p0`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// Manual position among siblings, lowest first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assignees: Vec<String>,
+    /// Lease taken by `claim`; the claimer is released once it expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claim: Option<Claim>,
+    /// Milestone name; sub-issues inherit their parent's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// Date the work should be finished by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    /// Date the work is planned to begin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<NaiveDate>,
+    /// Expected effort, in the unit set by `estimate.unit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<f64>,
+    /// Time logged with `track`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub work: Vec<WorkEntry>,
+    /// Issue number in the tracker it was imported from, e.g. `github#12`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Custom field values, typed by the `fields` config
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// One `- [ ]` line of an issue's content
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ChecklistItem {
+    pub checked: bool,
+    pub text: String,
+}
+
+/// Parse a checklist line into whether it is ticked, its text, and the byte offset of the mark
+pub(crate) fn checklist_item(line: &str) -> Option<(bool, &str, usize)> {
+    let body = line.trim_start();
+    let indent = line.len() - body.len();
+    let rest = body.strip_prefix(['-', '*', '+'])?.strip_prefix(" [")?;
+    let checked = match rest.chars().next()? {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    let text = rest[1..].strip_prefix(']')?;
+    Some((checked, text.trim(), indent + 3))
+}
+
+/// One block of logged work
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WorkEntry {
+    pub at: DateTime<Utc>,
+    pub by: String,
+    pub minutes: i64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Claim {
+    pub by: String,
+    pub until: DateTime<Utc>,
+}
+
+/// ID of the direct parent of `id` (`001-002` for `001-002-003`), if it is a sub-issue
+pub fn parent_of(id: &str) -> Option<&str> {
+    id.rsplit_once('-').map(|(parent, _)| parent)
+}
+
+impl Issue {
+    /// Append a comment entry such as `+++ text`, recording when it was made
+    pub fn add_comment(&mut self, entry: &str, at: DateTime<Utc>) {
+        self.comment_times.resize(self.comments.len(), None);
+        self.comment_times.push(Some(at));
+        self.comments.push(entry.to_string());
+    }
+
+    /// When comment `n` was added, if known
+    pub fn comment_time(&self, n: usize) -> Option<DateTime<Utc>> {
+        self.comment_times.get(n).copied().flatten()
+    }
+
+    /// ID of the direct parent, if this is a sub-issue
+    pub fn parent_id(&self) -> Option<&str> {
+        parent_of(&self.id)
+    }
+
+    /// Sort key placing prioritised issues first, most urgent first
+    pub fn priority_key(&self) -> u32 {
+        self.priority.map_or(u32::MAX, u32::from)
+    }
+
+    /// Sort key placing ranked issues first, in rank order
+    pub fn rank_key(&self) -> u32 {
+        self.rank.unwrap_or(u32::MAX)
+    }
+
+    /// The claim, if its lease has not yet expired at `now`
+    pub fn active_claim(&self, now: DateTime<Utc>) -> Option<&Claim> {
+        self.claim.as_ref().filter(|c| c.until > now)
+    }
+
+    pub fn is_assigned_to(&self, name: &str) -> bool {
+        self.assignees.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+
+    /// Checklist items (`- [ ]` / `- [x]` lines) in the content, in order
+    pub fn checklist(&self) -> Vec<ChecklistItem> {
+        self.content
+            .lines()
+            .filter_map(checklist_item)
+            .map(|(checked, text, _)| ChecklistItem { checked, text: text.to_string() })
+            .collect()
+    }
+
+    /// Tick or untick the `n`th checklist item, counting from 1
+    pub fn set_checked(&mut self, n: usize, checked: bool) -> Result<()> {
+        let mut seen = 0;
+        let mut lines: Vec<String> = self.content.split('\n').map(String::from).collect();
+        for line in lines.iter_mut() {
+            let Some((_, _, mark)) = checklist_item(line) else { continue };
+            seen += 1;
+            if seen == n {
+                line.replace_range(mark..mark + 1, if checked { "x" } else { " " });
+                self.content = lines.join("\n");
+                return Ok(());
+            }
+        }
+        bail!("no checklist item {} (issue has {})", n, seen)
+    }
+
+    /// Total minutes logged on this issue alone
+    pub fn spent_minutes(&self) -> i64 {
+        self.work.iter().map(|w| w.minutes).sum()
+    }
+
+    /// Days until the due date, negative once overdue
+    pub fn days_until_due(&self, today: NaiveDate) -> Option<i64> {
+        self.due.map(|due| (due - today).num_days())
+    }
+}
+
+/// Render minutes compactly, e.g. `1h30m`, `45m` or `0m`
+pub fn format_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+/// Parse a `YYYY-MM-DD` date, or `none` to clear it
+pub fn parse_date_or_none(text: &str) -> Result<Option<NaiveDate>> {
+    match text.trim() {
+        "none" => Ok(None),
+        date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => Ok(Some(date)),
+            Err(_) => bail!("invalid date '{}' (expected YYYY-MM-DD or none)", text),
+        },
+    }
+}
+
+/// Parse a priority: `p0`..`p3`, a number, or `none`
+pub fn parse_priority(text: &str) -> Result<Option<u8>> {
+    let value = text.trim().to_lowercase();
+    match value.as_str() {
+        "none" => Ok(None),
+        v => match v.strip_prefix('p').unwrap_or(v).parse() {
+            Ok(n) => Ok(Some(n)),
+            Err(_) => bail!("invalid priority '{}' (expected p0-p3, a number, or none)", text),
+        },
+    }
+}
+
+/// Parse a compact duration such as `90m`, `1h30m`, `2d` or `45s`
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for c in text.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let Ok(n) = digits.parse::<i64>() else {
+            bail!("invalid duration '{}'", text);
+        };
+        total += match c {
+            'd' => Duration::days(n),
+            'h' => Duration::hours(n),
+            'm' => Duration::minutes(n),
+            's' => Duration::seconds(n),
+            _ => bail!("invalid duration '{}' (use d, h, m or s)", text),
+        };
+        digits.clear();
+    }
+    if !digits.is_empty() || text.trim().is_empty() {
+        bail!("invalid duration '{}' (use d, h, m or s)", text);
+    }
+    Ok(total)
 }
 
 #[cfg(test)]
@@ -18,8 +239,9 @@ mod tests {
 
     #[test]
     fn test_issue_state_to_string() {
-        assert_eq!(State::Open.to_string(), "open");
-        assert_eq!(State::Closed.to_string(), "closed");
+        assert_eq!(State::open().to_string(), "open");
+        assert_eq!(State::closed().to_string(), "closed");
+        assert_eq!(State::from("In-Progress").to_string(), "in-progress");
     }
 
     #[test]
@@ -29,27 +251,171 @@ mod tests {
             title: "Test".to_string(),
             content: "Body".to_string(),
             labels: vec!["bug".to_string()],
-            state: State::Open,
+            state: State::open(),
             comments: vec!["First comment".to_string()],
+            ..Default::default()
         };
         assert_eq!(issue.id, "001");
-        assert_eq!(issue.state, State::Open);
+        assert_eq!(issue.state, State::open());
         assert_eq!(issue.labels, vec!["bug"]);
         assert_eq!(issue.comments.len(), 1);
     }
+
+    #[test]
+    fn test_comment_times_line_up_with_old_comments() {
+        let mut issue = Issue { comments: vec!["+++ old".to_string()], ..Default::default() };
+        let at = "2026-03-01T09:30:00Z".parse().unwrap();
+        issue.add_comment("+++ new", at);
+        assert_eq!(issue.comment_time(0), None);
+        assert_eq!(issue.comment_time(1), Some(at));
+        assert_eq!(issue.comments[1], "+++ new");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("2d").unwrap(), Duration::hours(48));
+        assert!(parse_duration("90").is_err());
+        assert!(parse_duration("1w").is_err());
+        assert_eq!(format_minutes(90), "1h30m");
+        assert_eq!(format_minutes(120), "2h");
+        assert_eq!(format_minutes(0), "0m");
+    }
+
+    #[test]
+    fn test_checklist_toggle() {
+        let mut issue = Issue {
+            content: "Intro\n- [ ] parse\n  * [X] nested\n- [] not an item\n+ [ ] docs".to_string(),
+            ..Default::default()
+        };
+        let items = issue.checklist();
+        assert_eq!(items.len(), 3);
+        assert!(items[1].checked);
+        assert_eq!(items[2].text, "docs");
+        issue.set_checked(1, true).unwrap();
+        issue.set_checked(2, false).unwrap();
+        assert_eq!(issue.content, "Intro\n- [x] parse\n  * [ ] nested\n- [] not an item\n+ [ ] docs");
+        assert!(issue.set_checked(4, true).is_err());
+    }
+
+    #[test]
+    fn test_days_until_due() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let issue = Issue { due: NaiveDate::from_ymd_opt(2026, 3, 8), ..Default::default() };
+        assert_eq!(issue.days_until_due(today), Some(-2));
+        assert_eq!(Issue::default().days_until_due(today), None);
+        assert_eq!(parse_date_or_none("none").unwrap(), None);
+        assert!(parse_date_or_none("10/03/2026").is_err());
+    }
+
+    #[test]
+    fn test_progress_excludes_dropped() {
+        let closed = |reason| Issue {
+            state: State::closed(),
+            close_reason: reason,
+            ..Default::default()
+        };
+        let issues = [
+            Issue::default(),
+            closed(None),
+            closed(Some(CloseReason::Completed)),
+            closed(Some(CloseReason::Wontfix)),
+        ];
+        let progress = Progress::tally(issues.iter(), |i| i.state == State::closed());
+        assert_eq!((progress.done(), progress.total()), (2, 3));
+        assert_eq!(progress.wontfix, 1);
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
-pub enum State {
-    Open,
-    Closed,
+/// Workflow state name, lowercased; which states exist and which are terminal is set by config
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct State(String);
+
+impl Default for State {
+    fn default() -> Self {
+        State::open()
+    }
+}
+
+impl State {
+    pub fn open() -> State {
+        State("open".into())
+    }
+
+    pub fn closed() -> State {
+        State("closed".into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for State {
+    fn from(name: &str) -> Self {
+        State(name.trim().to_lowercase())
+    }
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
-impl ToString for State {
-    fn to_string(&self) -> String {
+/// Structured reason recorded when an issue is closed
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseReason {
+    Completed,
+    Wontfix,
+    Duplicate,
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            State::Open => "open".into(),
-            State::Closed => "closed".into(),
+            CloseReason::Completed => write!(f, "completed"),
+            CloseReason::Wontfix => write!(f, "wontfix"),
+            CloseReason::Duplicate => write!(f, "duplicate"),
+        }
+    }
+}
+
+/// Completion counts over a set of issues; closures other than `completed` are dropped from progress
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Progress {
+    pub open: usize,
+    pub completed: usize,
+    pub wontfix: usize,
+    pub duplicate: usize,
+}
+
+impl Progress {
+    /// Count issues, using `is_closed` to decide which states are terminal
+    pub fn tally<'a>(issues: impl IntoIterator<Item = &'a Issue>, is_closed: impl Fn(&Issue) -> bool) -> Progress {
+        let mut progress = Progress::default();
+        for issue in issues {
+            if !is_closed(issue) {
+                progress.open += 1;
+                continue;
+            }
+            match issue.close_reason {
+                None | Some(CloseReason::Completed) => progress.completed += 1,
+                Some(CloseReason::Wontfix) => progress.wontfix += 1,
+                Some(CloseReason::Duplicate) => progress.duplicate += 1,
+            }
         }
+        progress
+    }
+
+    pub fn done(&self) -> usize {
+        self.completed
+    }
+
+    /// Issues that count towards progress: open plus completed
+    pub fn total(&self) -> usize {
+        self.open + self.completed
     }
 }
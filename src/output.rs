@@ -0,0 +1,46 @@
+// Command output, printed to stdout or captured for the MCP server
+
+use std::cell::RefCell;
+
+thread_local! {
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Like `print!`, but captured while inside `output::capture`
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write(&format!($($arg)*))
+    };
+}
+
+/// Like `println!`, but captured while inside `output::capture`
+macro_rules! outln {
+    () => {
+        $crate::output::write("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write(&format!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+pub fn write(text: &str) {
+    CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(buffer) => buffer.push_str(text),
+        None => print!("{}", text),
+    })
+}
+
+/// Run `f`, collecting what it prints instead of writing it to stdout
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let outer = CAPTURED.with(|captured| captured.borrow_mut().replace(String::new()));
+    let result = f();
+    let text = CAPTURED.with(|captured| std::mem::replace(&mut *captured.borrow_mut(), outer));
+    (result, text.unwrap_or_default())
+}
+
+pub(crate) use {out, outln};
+
+/// Whether output goes to the terminal rather than into a capture
+pub fn is_captured() -> bool {
+    CAPTURED.with(|captured| captured.borrow().is_some())
+}
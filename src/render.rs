@@ -0,0 +1,209 @@
+// Terminal rendering for `view`: markdown layout, labelled comments, colors and paging
+
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use crossterm::style::{StyledContent, Stylize};
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use crate::models::checklist_item;
+
+type Paint = fn(StyledContent<String>) -> StyledContent<String>;
+
+/// Lays out issue text for the terminal, with or without ANSI colors
+#[derive(Clone, Copy, Debug)]
+pub struct Renderer {
+    pub color: bool,
+}
+
+impl Renderer {
+    /// Colors for terminals, unless `--no-color` or a non-empty `NO_COLOR` turns them off
+    pub fn detect(no_color: bool) -> Renderer {
+        let disabled = no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Renderer { color: !disabled && std::io::stdout().is_terminal() }
+    }
+
+    fn paint(&self, text: &str, paint: Paint) -> String {
+        match self.color {
+            true => paint(text.to_string().stylize()).to_string(),
+            false => text.to_string(),
+        }
+    }
+
+    pub fn title(&self, text: &str) -> String {
+        self.paint(text, |s| s.bold())
+    }
+
+    pub fn dim(&self, text: &str) -> String {
+        self.paint(text, |s| s.dim())
+    }
+
+    /// Markdown content: headings, checklists, bullets, quotes and fenced code
+    ///
+    /// Without colors, heading marks and inline `**`/`` ` `` markers stay, since they carry the meaning.
+    pub fn markdown(&self, text: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut in_code = false;
+        for line in text.lines() {
+            let body = line.trim_start();
+            let indent = &line[..line.len() - body.len()];
+            if body.starts_with("```") {
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                lines.push(self.paint(&format!("    {}", line), |s| s.yellow()));
+                continue;
+            }
+            let rendered = if let Some(heading) = heading(body) {
+                match self.color {
+                    true => self.paint(heading, |s| s.bold().underlined().cyan()),
+                    false => line.to_string(),
+                }
+            } else if let Some((checked, item, _)) = checklist_item(line) {
+                match checked {
+                    true => format!("{}{} {}", indent, self.paint("☑", |s| s.green()), self.dim(item)),
+                    false => format!("{}☐ {}", indent, self.inline(item)),
+                }
+            } else if let Some(item) = body.strip_prefix("- ").or(body.strip_prefix("* ")) {
+                format!("{}• {}", indent, self.inline(item))
+            } else if let Some(quote) = body.strip_prefix('>') {
+                format!("{}{}", indent, self.paint(&format!("│ {}", quote.trim_start()), |s| s.dim()))
+            } else {
+                format!("{}{}", indent, self.inline(body))
+            };
+            lines.push(rendered);
+        }
+        lines
+    }
+
+    /// `**bold**` and `` `code` `` spans within a line
+    fn inline(&self, text: &str) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+        let mut out = String::new();
+        let mut span = String::new();
+        let (mut bold, mut code) = (false, false);
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            let toggles_bold = c == '*' && !code && chars.peek() == Some(&'*');
+            if !toggles_bold && c != '`' {
+                span.push(c);
+                continue;
+            }
+            if toggles_bold {
+                chars.next();
+            }
+            let text = std::mem::take(&mut span);
+            out.push_str(&match (bold, code) {
+                (true, _) => self.paint(&text, |s| s.bold()),
+                (_, true) => self.paint(&text, |s| s.yellow()),
+                _ => text,
+            });
+            match toggles_bold {
+                true => bold = !bold,
+                false => code = !code,
+            }
+        }
+        // An unclosed marker is shown as written
+        let marker = if bold { "**" } else if code { "`" } else { "" };
+        out.push_str(marker);
+        out.push_str(&span);
+        out
+    }
+
+    /// A stored comment entry labelled by kind, with its time when known
+    pub fn comment(&self, entry: &str, at: Option<DateTime<Utc>>) -> Vec<String> {
+        let (kind, text) = comment_kind(entry);
+        let paint: Paint = match kind {
+            "closed" => |s| s.red().bold(),
+            "reopened" => |s| s.green().bold(),
+            "moved" => |s| s.yellow().bold(),
+            _ => |s| s.blue().bold(),
+        };
+        let mut header = self.paint(kind, paint);
+        if let Some(at) = at {
+            header.push_str(&self.dim(&format!(" · {}", at.with_timezone(&Local).format("%Y-%m-%d %H:%M"))));
+        }
+        let mut lines = vec![header];
+        lines.extend(text.lines().map(|line| format!("  {}", self.inline(line))));
+        lines
+    }
+}
+
+/// Text of a `#` to `######` heading line
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    ((1..=6).contains(&level) && text.starts_with(' ')).then(|| text.trim())
+}
+
+/// Kind of a stored comment entry from its marker, and the text after it
+pub(crate) fn comment_kind(entry: &str) -> (&'static str, &str) {
+    for (marker, kind) in [("+++", "comment"), (">>>", "closed"), ("<<<", "reopened"), ("~~~", "moved")] {
+        if let Some(text) = entry.strip_prefix(marker) {
+            return (kind, text.trim_start());
+        }
+    }
+    ("note", entry)
+}
+
+/// Print `lines`, through `$PAGER` (default `less -R`) when they would overflow the terminal
+pub fn page(lines: &[String], pager: bool) -> Result<()> {
+    let text = format!("{}\n", lines.join("\n"));
+    let overflows = crossterm::terminal::size().is_ok_and(|(_, height)| height > 0 && lines.len() >= usize::from(height));
+    if pager && overflows && std::io::stdout().is_terminal() && !crate::output::is_captured() {
+        let command = std::env::var("PAGER").ok().filter(|p| !p.trim().is_empty());
+        let command = command.unwrap_or_else(|| "less -R".to_string());
+        let mut words = command.split_whitespace();
+        let program = words.next().unwrap_or("less");
+        // A missing pager is not an error: the text is printed instead
+        if let Ok(mut child) = Command::new(program).args(words).stdin(Stdio::piped()).spawn() {
+            if let Some(mut stdin) = child.stdin.take() {
+                // The reader may quit before reading everything
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            child.wait()?;
+            return Ok(());
+        }
+    }
+    crate::output::write(&text);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "## Steps\n- [ ] open **login**\n- [x] done\n* note `x`\n```\nlet a = 1;\n```\n> quoted";
+
+    #[test]
+    fn test_markdown_without_color() {
+        let plain = Renderer { color: false };
+        assert_eq!(
+            plain.markdown(CONTENT),
+            vec!["## Steps", "☐ open **login**", "☑ done", "• note `x`", "    let a = 1;", "│ quoted"]
+        );
+    }
+
+    #[test]
+    fn test_markdown_with_color() {
+        let color = Renderer { color: true };
+        let lines = color.markdown(CONTENT);
+        assert!(lines[0].contains("Steps") && !lines[0].contains('#') && lines[0].contains('\u{1b}'));
+        assert!(lines[1].starts_with("☐ open ") && !lines[1].contains("**"));
+        assert!(!lines[3].contains('`'));
+        assert_eq!(color.inline("a **b"), "a **b");
+    }
+
+    #[test]
+    fn test_comment_kinds() {
+        let plain = Renderer { color: false };
+        assert_eq!(plain.comment("+++ hello", None), vec!["comment", "  hello"]);
+        assert_eq!(plain.comment(">>> wontfix: dropped", None), vec!["closed", "  wontfix: dropped"]);
+        assert_eq!(plain.comment("<<< back", None)[0], "reopened");
+        assert_eq!(plain.comment("~~~ review", None)[0], "moved");
+        let at = "2026-03-01T09:30:00Z".parse().unwrap();
+        assert!(plain.comment("+++ hi", Some(at))[0].starts_with("comment · 2026-03-0"));
+    }
+}
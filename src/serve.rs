@@ -0,0 +1,197 @@
+// Local HTTP JSON API: `git issue serve`
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use crate::storage::{path_for, STORAGE_DIR};
+
+/// Answer requests on 127.0.0.1 until the process is stopped
+pub fn run(port: u16) -> Result<()> {
+    std::fs::create_dir_all(STORAGE_DIR)?;
+    let server = tiny_http::Server::http(("127.0.0.1", port)).map_err(|e| anyhow!("cannot listen on port {}: {}", port, e))?;
+    match server.server_addr().to_ip() {
+        Some(addr) => println!("listening on http://{}", addr),
+        None => println!("listening on port {}", port),
+    }
+    for mut request in server.incoming_requests() {
+        let header = |name: &'static str| request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.to_string());
+        let (host, content_type) = (header("Host"), header("Content-Type"));
+        let method = request.method().as_str().to_string();
+        let mut body = String::new();
+        let (status, reply) = match check_headers(&method, host.as_deref(), content_type.as_deref()) {
+            Some(refusal) => refusal,
+            None => match request.as_reader().read_to_string(&mut body) {
+                Ok(_) => respond(&method, request.url(), &body),
+                Err(e) => (400, json!({ "error": format!("unreadable body: {}", e) })),
+            },
+        };
+        eprintln!("{} {} {}", request.method(), request.url(), status);
+        let header = tiny_http::Header::from_bytes("Content-Type", "application/json").expect("valid header");
+        let response = tiny_http::Response::from_string(format!("{}\n", reply)).with_status_code(status).with_header(header);
+        // A client that hung up is not the server's problem
+        let _ = request.respond(response);
+    }
+    Ok(())
+}
+
+/// Refuse requests a web page could make from another origin
+///
+/// Browsers send the page's own host name, so a DNS name rebound to 127.0.0.1 fails the `Host`
+/// check, and they cannot send a JSON content type cross-origin without asking first.
+fn check_headers(method: &str, host: Option<&str>, content_type: Option<&str>) -> Option<(u16, Value)> {
+    let name = host.map(|h| h.rsplit_once(':').filter(|(_, port)| port.parse::<u16>().is_ok()).map_or(h, |(name, _)| name));
+    if !matches!(name, Some("127.0.0.1" | "localhost")) {
+        return Some((403, json!({ "error": format!("host {} is not allowed", host.unwrap_or("(none)")) })));
+    }
+    let json = content_type.and_then(|c| c.split(';').next()).is_some_and(|c| c.trim().eq_ignore_ascii_case("application/json"));
+    if matches!(method, "POST" | "PATCH") && !json {
+        return Some((415, json!({ "error": "request body must be sent as application/json" })));
+    }
+    None
+}
+
+/// Status code and JSON reply for one request
+fn respond(method: &str, url: &str, body: &str) -> (u16, Value) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let body = match body.trim() {
+        "" => json!({}),
+        text => match serde_json::from_str::<Value>(text) {
+            Ok(value) if value.is_object() => value,
+            _ => return (400, json!({ "error": "request body must be a JSON object" })),
+        },
+    };
+    let with_id = |id: &str| {
+        let mut arguments = body.clone();
+        arguments["id"] = json!(id);
+        arguments
+    };
+    let (command, arguments, created) = match (method, segments.as_slice()) {
+        ("GET", ["issues"]) => ("ls", filter(query), false),
+        ("POST", ["issues"]) => ("create", body.clone(), true),
+        ("GET", ["issues", id]) => ("view", with_id(id), false),
+        ("PATCH", ["issues", id]) => ("edit", with_id(id), false),
+        ("POST", ["issues", id, "comments"]) => ("comment", with_id(id), false),
+        ("POST", ["issues", id, "state"]) => ("status", with_id(id), false),
+        ("POST", ["issues", id, "close"]) => ("close", with_id(id), false),
+        ("POST", ["issues", id, "reopen"]) => ("reopen", with_id(id), false),
+        ("POST", ["issues", id, "assignees"]) => ("assign", with_id(id), false),
+        ("POST", ["issues", id, "claim"]) => ("claim", with_id(id), false),
+        ("POST", ["issues", id, "release"]) => ("release", with_id(id), false),
+        ("POST", ["issues", id, "priority"]) => ("prioritize", with_id(id), false),
+        ("POST", ["issues", id, "reorder"]) => ("reorder", with_id(id), false),
+        ("POST", ["issues", id, "check"]) => ("check", with_id(id), false),
+        ("POST", ["issues", id, "uncheck"]) => ("uncheck", with_id(id), false),
+        ("POST", ["issues", id, "estimate"]) => ("estimate", with_id(id), false),
+        ("POST", ["issues", id, "time"]) => ("track", with_id(id), false),
+        ("GET", ["search"]) => ("search", filter(query), false),
+        ("POST", ["plan"]) => ("plan", body.clone(), true),
+        ("POST", ["claim"]) => ("claim", body.clone(), false),
+        ("GET", ["next"]) => ("next", json!({}), false),
+        (
+            _,
+            ["issues"]
+            | ["issues", _]
+            | [
+                "issues",
+                _,
+                "comments" | "state" | "close" | "reopen" | "assignees" | "claim" | "release" | "priority" | "reorder"
+                | "check" | "uncheck" | "estimate" | "time",
+            ]
+            | ["search"]
+            | ["plan"]
+            | ["claim"]
+            | ["next"],
+        ) => {
+            return (405, json!({ "error": format!("{} is not supported on {}", method, path) }));
+        }
+        _ => return (404, json!({ "error": format!("no route for {}", path) })),
+    };
+    if let Some(id) = arguments.get("id").and_then(Value::as_str) {
+        if !path_for(id).is_ok_and(|path| path.exists()) {
+            return (404, json!({ "error": format!("no issue {}", id) }));
+        }
+    }
+    let (result, _) = crate::output::capture(|| crate::api::call(command, arguments));
+    match result {
+        Ok(value) if created => (201, value),
+        Ok(value) => (200, value),
+        Err(e) => (422, json!({ "error": format!("{:#}", e) })),
+    }
+}
+
+/// `ls` arguments from a query string such as `state=all&label=bug&label=ui`
+fn filter(query: &str) -> Value {
+    let mut arguments = json!({});
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = decode(value);
+        match decode(key).as_str() {
+            "label" => match arguments["label"].as_array_mut() {
+                Some(labels) => labels.push(json!(value)),
+                None => arguments["label"] = json!([value]),
+            },
+            key => arguments[key] = json!(value),
+        }
+    }
+    arguments
+}
+
+/// Undo URL encoding: `+` for spaces and `%XX` escapes
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_filter() {
+        assert_eq!(
+            filter("state=all&label=type%3A%3Abug&label=good+first&root=001"),
+            json!({ "state": "all", "label": ["type::bug", "good first"], "root": "001" })
+        );
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn test_header_checks() {
+        assert_eq!(check_headers("GET", Some("127.0.0.1:7878"), None), None);
+        assert_eq!(check_headers("GET", Some("localhost"), None), None);
+        assert_eq!(check_headers("POST", Some("localhost:80"), Some("application/json; charset=utf-8")), None);
+        assert_eq!(check_headers("GET", Some("evil.example:7878"), None).unwrap().0, 403);
+        assert_eq!(check_headers("GET", Some("localhost.evil.example"), None).unwrap().0, 403);
+        assert_eq!(check_headers("GET", None, None).unwrap().0, 403);
+        assert_eq!(check_headers("POST", Some("localhost"), Some("text/plain")).unwrap().0, 415);
+        assert_eq!(check_headers("PATCH", Some("localhost"), None).unwrap().0, 415);
+    }
+
+    #[test]
+    fn test_routes_checked_before_commands() {
+        assert_eq!(respond("GET", "/nowhere", "").0, 404);
+        assert_eq!(respond("DELETE", "/issues/001", "").0, 405);
+        assert_eq!(respond("POST", "/issues", "[1]").0, 400);
+        assert_eq!(respond("GET", "/issues/..%2Fsecret", "").0, 404);
+    }
+}
@@ -0,0 +1,411 @@
+// Static HTML site for `git issue export html`
+
+use crate::config::Workflow;
+use crate::labels::LabelDef;
+use crate::milestones::Milestone;
+use crate::models::{checklist_item, Issue, Progress};
+use crate::render::comment_kind;
+use std::collections::{BTreeSet, HashMap};
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:64rem;margin:2rem auto;padding:0 1rem;color:#222}\
+a{color:#0b5cad;text-decoration:none}a:hover{text-decoration:underline}\
+table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:.3rem .5rem;border-bottom:1px solid #ddd;vertical-align:top}\
+.state{display:inline-block;padding:0 .4rem;border-radius:.6rem;font-size:.85em;background:#dafbe1}.state.closed{background:#eee;color:#666}\
+.label{display:inline-block;padding:0 .4rem;margin-right:.2rem;border-radius:.6rem;font-size:.85em;background:#ddf4ff}\
+.meta{color:#555}.filters{margin:1rem 0}.filters input,.filters select{margin-right:.5rem}\
+pre{background:#f6f8fa;padding:.6rem;overflow:auto}blockquote{border-left:3px solid #ddd;margin:0;padding-left:.8rem;color:#555}\
+ul.checklist{list-style:none;padding-left:1rem}.timeline li{margin-bottom:.6rem}.kind{font-weight:bold}time{color:#777;margin-left:.4rem}";
+
+const FILTER_SCRIPT: &str = "const q=document.getElementById('q'),s=document.getElementById('state'),l=document.getElementById('label');\n\
+function apply(){const t=q.value.toLowerCase();for(const r of document.querySelectorAll('tbody tr')){\
+const d=r.dataset,st=s.value;\
+const state=!st||(st==='open'?d.open==='1':st==='closed'?d.open==='0':d.state===st);\
+r.hidden=!(state&&(!t||d.text.includes(t))&&(!l.value||JSON.parse(d.labels).includes(l.value)));}}\n\
+for(const e of [q,s,l])e.addEventListener('input',apply);\n";
+
+struct Site<'a> {
+    issues: &'a [Issue],
+    by_id: HashMap<&'a str, &'a Issue>,
+    workflow: &'a Workflow,
+}
+
+/// Every page of the site as (path relative to the output directory, HTML), built from the store alone
+pub fn build(issues: &[Issue], milestones: &[Milestone], labels: &[LabelDef], workflow: &Workflow) -> Vec<(String, String)> {
+    let site = Site { issues, by_id: issues.iter().map(|i| (i.id.as_str(), i)).collect(), workflow };
+
+    let mut label_names: BTreeSet<&str> = labels.iter().map(|l| l.name.as_str()).collect();
+    label_names.extend(issues.iter().flat_map(|i| i.labels.iter().map(String::as_str)));
+    let mut milestone_names: Vec<&str> = milestones.iter().map(|m| m.name.as_str()).collect();
+    for issue in issues {
+        if let Some(name) = issue.milestone.as_deref().filter(|name| !milestone_names.contains(name)) {
+            milestone_names.push(name);
+        }
+    }
+
+    let mut pages = vec![("index.html".to_string(), site.index(&label_names, &milestone_names))];
+    for issue in issues {
+        pages.push((format!("issues/{}.html", issue.id), site.issue_page(issue)));
+    }
+    for name in &label_names {
+        let def = labels.iter().find(|l| l.name == *name);
+        pages.push((format!("labels/{}.html", slug(name)), site.label_page(name, def)));
+    }
+    for name in &milestone_names {
+        let def = milestones.iter().find(|m| m.name == *name);
+        pages.push((format!("milestones/{}.html", slug(name)), site.milestone_page(name, def)));
+    }
+    pages
+}
+
+impl Site<'_> {
+    fn index(&self, labels: &BTreeSet<&str>, milestones: &[&str]) -> String {
+        let mut states = String::from("<option value=\"\">any state</option><option value=\"open\">open</option><option value=\"closed\">closed</option>");
+        for state in &self.workflow.states {
+            if state.name != "open" && state.name != "closed" {
+                states.push_str(&format!("<option>{}</option>", escape(&state.name)));
+            }
+        }
+        let label_options: String = labels.iter().map(|l| format!("<option>{}</option>", escape(l))).collect();
+        let mut body = format!(
+            "<h1>Issues</h1>\n<div class=\"filters\"><input id=\"q\" type=\"search\" placeholder=\"Filter by text\">\
+<select id=\"state\">{}</select><select id=\"label\"><option value=\"\">any label</option>{}</select></div>\n",
+            states, label_options
+        );
+        body.push_str(&self.table(self.issues.iter(), ""));
+        body.push_str(&format!("<h2>Labels</h2>\n<p>{}</p>\n", self.label_links(labels.iter().copied(), "")));
+        if !milestones.is_empty() {
+            let links: Vec<String> = milestones
+                .iter()
+                .map(|m| format!("<a href=\"milestones/{}.html\">{}</a>", slug(m), escape(m)))
+                .collect();
+            body.push_str(&format!("<h2>Milestones</h2>\n<p>{}</p>\n", links.join(" · ")));
+        }
+        body.push_str(&format!("<script>\n{}</script>\n", FILTER_SCRIPT));
+        page("Issues", "", &body)
+    }
+
+    fn issue_page(&self, issue: &Issue) -> String {
+        let mut body = String::new();
+        let ancestors: Vec<String> = ancestor_ids(&issue.id)
+            .filter_map(|id| self.by_id.get(id))
+            .map(|parent| format!("<a href=\"{}.html\">{} {}</a>", parent.id, parent.id, escape(&parent.title)))
+            .collect();
+        if !ancestors.is_empty() {
+            body.push_str(&format!("<p class=\"meta\">{}</p>\n", ancestors.join(" › ")));
+        }
+        body.push_str(&format!("<h1>{} · {}</h1>\n", issue.id, escape(&issue.title)));
+
+        let mut meta = vec![self.state_badge(issue)];
+        if !issue.labels.is_empty() {
+            meta.push(self.label_links(issue.labels.iter().map(String::as_str), "../"));
+        }
+        if let Some(milestone) = crate::milestones::effective(issue, &self.by_id) {
+            meta.push(format!("milestone <a href=\"../milestones/{}.html\">{}</a>", slug(milestone), escape(milestone)));
+        }
+        if !issue.assignees.is_empty() {
+            let names: Vec<String> = issue.assignees.iter().map(|a| format!("@{}", escape(a))).collect();
+            meta.push(names.join(", "));
+        }
+        if let Some(priority) = issue.priority {
+            meta.push(format!("p{}", priority));
+        }
+        if let Some(due) = issue.due {
+            meta.push(format!("due {}", due));
+        }
+        body.push_str(&format!("<p class=\"meta\">{}</p>\n", meta.join(" · ")));
+        body.push_str(&format!("<div class=\"content\">\n{}</div>\n", markdown(&issue.content)));
+
+        let prefix = format!("{}-", issue.id);
+        let descendants = self.issues.iter().filter(|i| i.id.starts_with(&prefix));
+        let progress = Progress::tally(descendants, |i| self.workflow.is_terminal(&i.state));
+        if progress.total() > 0 {
+            body.push_str(&format!("<h2>Sub-issues ({}/{})</h2>\n", progress.done(), progress.total()));
+            body.push_str(&self.tree(&issue.id));
+        }
+        if !issue.comments.is_empty() {
+            body.push_str("<h2>Timeline</h2>\n<ol class=\"timeline\">\n");
+            for (n, entry) in issue.comments.iter().enumerate() {
+                let (kind, text) = comment_kind(entry);
+                let time = issue
+                    .comment_time(n)
+                    .map(|at| format!("<time>{}</time>", at.format("%Y-%m-%d %H:%M UTC")))
+                    .unwrap_or_default();
+                body.push_str(&format!(
+                    "<li><span class=\"kind\">{}</span>{}<div>{}</div></li>\n",
+                    kind,
+                    time,
+                    inline(&escape(text))
+                ));
+            }
+            body.push_str("</ol>\n");
+        }
+        page(&format!("{} {}", issue.id, issue.title), "../", &body)
+    }
+
+    fn label_page(&self, name: &str, def: Option<&LabelDef>) -> String {
+        let mut body = format!("<h1>Label <span class=\"label\">{}</span></h1>\n", escape(name));
+        if let Some(description) = def.map(|d| d.description.as_str()).filter(|d| !d.is_empty()) {
+            body.push_str(&format!("<p>{}</p>\n", escape(description)));
+        }
+        body.push_str(&self.table(self.issues.iter().filter(|i| i.labels.iter().any(|l| l == name)), "../"));
+        page(&format!("Label {}", name), "../", &body)
+    }
+
+    fn milestone_page(&self, name: &str, def: Option<&Milestone>) -> String {
+        let members: Vec<&Issue> = self
+            .issues
+            .iter()
+            .filter(|i| crate::milestones::effective(i, &self.by_id) == Some(name))
+            .collect();
+        let progress = Progress::tally(members.iter().copied(), |i| self.workflow.is_terminal(&i.state));
+        let mut body = format!("<h1>Milestone {}</h1>\n<p class=\"meta\">{}/{} closed", escape(name), progress.done(), progress.total());
+        if let Some(due) = def.and_then(|m| m.due) {
+            body.push_str(&format!(" · due {}", due));
+        }
+        body.push_str("</p>\n");
+        if let Some(description) = def.map(|m| m.description.as_str()).filter(|d| !d.is_empty()) {
+            body.push_str(&format!("<p>{}</p>\n", escape(description)));
+        }
+        body.push_str(&self.table(members.into_iter(), "../"));
+        page(&format!("Milestone {}", name), "../", &body)
+    }
+
+    /// Issue table whose rows carry the data the index filters read
+    fn table<'b>(&self, issues: impl Iterator<Item = &'b Issue>, root: &str) -> String {
+        let mut html = String::from("<table>\n<thead><tr><th>ID</th><th>Title</th><th>State</th><th>Labels</th><th>Milestone</th></tr></thead>\n<tbody>\n");
+        for issue in issues {
+            let labels = serde_json::to_string(&issue.labels).unwrap_or_default();
+            let text = format!("{} {} {}", issue.id, issue.title, issue.labels.join(" ")).to_lowercase();
+            let milestone = crate::milestones::effective(issue, &self.by_id)
+                .map(|m| format!("<a href=\"{}milestones/{}.html\">{}</a>", root, slug(m), escape(m)))
+                .unwrap_or_default();
+            html.push_str(&format!(
+                "<tr data-state=\"{}\" data-open=\"{}\" data-labels=\"{}\" data-text=\"{}\">\
+<td><a href=\"{root}issues/{id}.html\">{id}</a></td><td style=\"padding-left:{depth}.5rem\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape(issue.state.as_str()),
+                if self.workflow.is_terminal(&issue.state) { 0 } else { 1 },
+                escape(&labels),
+                escape(&text),
+                escape(&issue.title),
+                self.state_badge(issue),
+                self.label_links(issue.labels.iter().map(String::as_str), root),
+                milestone,
+                root = root,
+                id = issue.id,
+                depth = issue.id.matches('-').count(),
+            ));
+        }
+        html.push_str("</tbody>\n</table>\n");
+        html
+    }
+
+    /// Nested list of the sub-issues below `id`
+    fn tree(&self, id: &str) -> String {
+        let children: Vec<&Issue> = self.issues.iter().filter(|i| i.parent_id() == Some(id)).collect();
+        if children.is_empty() {
+            return String::new();
+        }
+        let mut html = String::from("<ul>\n");
+        for child in children {
+            html.push_str(&format!(
+                "<li><a href=\"{}.html\">{}</a> {} {}\n{}</li>\n",
+                child.id,
+                child.id,
+                escape(&child.title),
+                self.state_badge(child),
+                self.tree(&child.id)
+            ));
+        }
+        html.push_str("</ul>\n");
+        html
+    }
+
+    fn state_badge(&self, issue: &Issue) -> String {
+        let class = if self.workflow.is_terminal(&issue.state) { "state closed" } else { "state" };
+        let text = match issue.close_reason {
+            Some(reason) => format!("{}: {}", issue.state, reason),
+            None => issue.state.to_string(),
+        };
+        format!("<span class=\"{}\">{}</span>", class, escape(&text))
+    }
+
+    fn label_links<'b>(&self, labels: impl Iterator<Item = &'b str>, root: &str) -> String {
+        labels
+            .map(|l| format!("<a class=\"label\" href=\"{}labels/{}.html\">{}</a>", root, slug(l), escape(l)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// IDs of an issue's ancestors, outermost first
+fn ancestor_ids(id: &str) -> impl Iterator<Item = &str> {
+    id.match_indices('-').map(move |(at, _)| &id[..at])
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<nav><a href=\"{}index.html\">All issues</a></nav>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        root,
+        body
+    )
+}
+
+/// File name for a label or milestone, safe in paths and URLs: `type::bug` becomes `type_3A_3Abug`
+///
+/// Every other byte is escaped as `_XX`, `_` included, so distinct names never share a page.
+fn slug(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' => out.push(char::from(byte)),
+            byte => out.push_str(&format!("_{:02X}", byte)),
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `**bold**` and `` `code` `` spans in already escaped text
+fn inline(text: &str) -> String {
+    let mut out = String::new();
+    let (mut bold, mut code) = (false, false);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '*' && !code && chars.peek() == Some(&'*') {
+            chars.next();
+            out.push_str(if bold { "</strong>" } else { "<strong>" });
+            bold = !bold;
+        } else if c == '`' {
+            out.push_str(if code { "</code>" } else { "<code>" });
+            code = !code;
+        } else {
+            out.push(c);
+        }
+    }
+    if code {
+        out.push_str("</code>");
+    }
+    if bold {
+        out.push_str("</strong>");
+    }
+    out
+}
+
+/// Markdown content as HTML: headings, paragraphs, lists, checklists, quotes and fenced code
+fn markdown(text: &str) -> String {
+    #[derive(PartialEq)]
+    enum Block {
+        None,
+        Paragraph,
+        List,
+        Checklist,
+        Code,
+    }
+    let close = |block: &Block| match block {
+        Block::Paragraph => "</p>\n",
+        Block::List | Block::Checklist => "</ul>\n",
+        Block::Code => "</code></pre>\n",
+        Block::None => "",
+    };
+
+    let mut html = String::new();
+    let mut block = Block::None;
+    for line in text.lines() {
+        let body = line.trim();
+        if block == Block::Code {
+            if body.starts_with("```") {
+                html.push_str(close(&block));
+                block = Block::None;
+            } else {
+                html.push_str(&escape(line));
+                html.push('\n');
+            }
+            continue;
+        }
+        let level = body.len() - body.trim_start_matches('#').len();
+        let heading = (1..=6).contains(&level) && body[level..].starts_with(' ');
+        let next = if body.starts_with("```") {
+            Block::Code
+        } else if checklist_item(line).is_some() {
+            Block::Checklist
+        } else if body.starts_with("- ") || body.starts_with("* ") {
+            Block::List
+        } else if body.is_empty() || heading || body.starts_with('>') {
+            Block::None
+        } else {
+            Block::Paragraph
+        };
+        if next != block || next == Block::Code {
+            html.push_str(close(&block));
+            html.push_str(match next {
+                Block::Paragraph => "<p>",
+                Block::List => "<ul>\n",
+                Block::Checklist => "<ul class=\"checklist\">\n",
+                Block::Code => "<pre><code>",
+                Block::None => "",
+            });
+        } else if next == Block::Paragraph {
+            html.push('\n');
+        }
+        block = next;
+
+        if let Some((checked, item, _)) = checklist_item(line) {
+            let checked = if checked { " checked" } else { "" };
+            html.push_str(&format!("<li><input type=\"checkbox\" disabled{}> {}</li>\n", checked, inline(&escape(item))));
+        } else if block == Block::List {
+            html.push_str(&format!("<li>{}</li>\n", inline(&escape(&body[2..]))));
+        } else if heading {
+            html.push_str(&format!("<h{0}>{1}</h{0}>\n", level, inline(&escape(body[level..].trim()))));
+        } else if let Some(quote) = body.strip_prefix('>') {
+            html.push_str(&format!("<blockquote>{}</blockquote>\n", inline(&escape(quote.trim()))));
+        } else if block == Block::Paragraph {
+            html.push_str(&inline(&escape(body)));
+        }
+    }
+    html.push_str(close(&block));
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html = markdown("## Goal <b>\nShip **it**\nsoon\n\n- [x] a\n- [ ] `b`\n- plain\n```\nx < y\n```\n> note");
+        assert_eq!(
+            html,
+            "<h2>Goal &lt;b&gt;</h2>\n<p>Ship <strong>it</strong>\nsoon</p>\n\
+<ul class=\"checklist\">\n<li><input type=\"checkbox\" disabled checked> a</li>\n\
+<li><input type=\"checkbox\" disabled> <code>b</code></li>\n</ul>\n\
+<ul>\n<li>plain</li>\n</ul>\n<pre><code>x &lt; y\n</code></pre>\n<blockquote>note</blockquote>\n"
+        );
+    }
+
+    #[test]
+    fn test_slug_and_ancestors() {
+        assert_eq!(slug("type::bug"), "type_3A_3Abug");
+        assert_eq!(slug("type--bug"), "type--bug");
+        assert_eq!(slug("v1.0 beta"), "v1.0_20beta");
+        assert_eq!(slug("v1.0-beta"), "v1.0-beta");
+        assert_eq!(slug("a_b"), "a_5Fb");
+        assert_eq!(slug("été"), "_C3_A9t_C3_A9");
+        assert_eq!(ancestor_ids("001-002-003").collect::<Vec<_>>(), vec!["001", "001-002"]);
+    }
+}
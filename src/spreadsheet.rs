@@ -0,0 +1,189 @@
+// CSV/TSV rows for `git issue export csv` and `git issue import csv`
+
+use anyhow::{bail, Context, Result};
+use crate::commands::{check_dates, field_text};
+use crate::config::Config;
+use crate::models::{parse_date_or_none, parse_priority, Issue};
+
+/// Columns every store has; custom fields from the config come on top
+pub const BUILTIN: &[&str] = &[
+    "id", "parent", "title", "state", "labels", "comments", "content", "assignees", "milestone", "priority",
+    "due", "start", "estimate",
+];
+
+/// Columns exported when none are chosen
+const DEFAULT: &[&str] = &["id", "parent", "title", "state", "labels", "comments"];
+
+/// One data row, numbered as in the file (the header is line 1)
+pub struct Row {
+    pub line: u64,
+    pub cells: Vec<(String, String)>,
+}
+
+impl Row {
+    /// Cell in `column`, if the file has that column
+    pub fn get(&self, column: &str) -> Option<&str> {
+        self.cells.iter().find(|(c, _)| c == column).map(|(_, v)| v.as_str())
+    }
+
+    /// Non-empty cell in `column`
+    pub fn value(&self, column: &str) -> Option<&str> {
+        self.get(column).map(str::trim).filter(|v| !v.is_empty())
+    }
+}
+
+pub fn delimiter(tsv: bool) -> u8 {
+    if tsv { b'\t' } else { b',' }
+}
+
+/// Validate chosen columns, defaulting to the core ones plus every custom field
+pub fn columns(chosen: &[String], config: &Config) -> Result<Vec<String>> {
+    if chosen.is_empty() {
+        let mut columns: Vec<String> = DEFAULT.iter().map(|c| c.to_string()).collect();
+        columns.extend(config.fields.keys().cloned());
+        return Ok(columns);
+    }
+    for column in chosen {
+        check_column(column, config)?;
+    }
+    Ok(chosen.to_vec())
+}
+
+fn check_column(column: &str, config: &Config) -> Result<()> {
+    if !BUILTIN.contains(&column) && !config.fields.contains_key(column) {
+        bail!(
+            "unknown column '{}' (expected one of {} or a custom field)",
+            column,
+            BUILTIN.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Value of `column` for an issue, as written to a cell
+fn cell(issue: &Issue, column: &str, config: &Config) -> String {
+    match column {
+        "id" => issue.id.clone(),
+        "parent" => issue.parent_id().unwrap_or_default().to_string(),
+        "title" => issue.title.clone(),
+        "state" => issue.state.to_string(),
+        "labels" => issue.labels.join(","),
+        "comments" => issue.comments.len().to_string(),
+        "content" => issue.content.clone(),
+        "assignees" => issue.assignees.join(","),
+        "milestone" => issue.milestone.clone().unwrap_or_default(),
+        "priority" => issue.priority.map(|p| format!("p{}", p)).unwrap_or_default(),
+        "due" => issue.due.map(|d| d.to_string()).unwrap_or_default(),
+        "start" => issue.start.map(|d| d.to_string()).unwrap_or_default(),
+        "estimate" => issue.estimate.map(|e| config.estimate.unit.format(e)).unwrap_or_default(),
+        field => issue.extra.get(field).map(field_text).unwrap_or_default(),
+    }
+}
+
+/// A header row and one row per issue
+pub fn write(issues: &[&Issue], columns: &[String], delimiter: u8, config: &Config) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(Vec::new());
+    writer.write_record(columns)?;
+    for issue in issues {
+        writer.write_record(columns.iter().map(|c| cell(issue, c, config)))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Parse rows, rejecting unknown columns in the header
+pub fn read(data: &str, delimiter: u8, config: &Config) -> Result<Vec<Row>> {
+    let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).from_reader(data.as_bytes());
+    let header: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    for column in &header {
+        check_column(column, config).context("line 1")?;
+    }
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |p| p.line());
+        let cells = header.iter().cloned().zip(record.iter().map(String::from)).collect();
+        rows.push(Row { line, cells });
+    }
+    Ok(rows)
+}
+
+/// Copy a row's editable cells onto an issue; an empty cell clears the value
+///
+/// `id`, `parent` and `state` are left to the caller, and `comments` is a count that cannot be imported.
+pub fn apply(issue: &mut Issue, row: &Row, config: &Config) -> Result<()> {
+    let list = |text: &str| -> Vec<String> {
+        text.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect()
+    };
+    for (column, value) in &row.cells {
+        let trimmed = value.trim();
+        match column.as_str() {
+            "id" | "parent" | "state" | "comments" => {}
+            "title" if trimmed.is_empty() => bail!("title cannot be empty"),
+            "title" => issue.title = trimmed.to_string(),
+            "content" => issue.content = value.clone(),
+            "labels" => {
+                let labels = list(trimmed);
+                crate::labels::check(&labels, &config.labels)?;
+                issue.labels = labels;
+            }
+            "assignees" => issue.assignees = list(trimmed),
+            "milestone" if trimmed.is_empty() => issue.milestone = None,
+            "milestone" => issue.milestone = Some(crate::milestones::find(trimmed)?.name),
+            "priority" if trimmed.is_empty() => issue.priority = None,
+            "priority" => issue.priority = parse_priority(trimmed)?,
+            "due" if trimmed.is_empty() => issue.due = None,
+            "due" => issue.due = parse_date_or_none(trimmed)?,
+            "start" if trimmed.is_empty() => issue.start = None,
+            "start" => issue.start = parse_date_or_none(trimmed)?,
+            "estimate" if trimmed.is_empty() => issue.estimate = None,
+            "estimate" => issue.estimate = Some(config.estimate.unit.parse(trimmed)?),
+            field => match config.parse_field(&format!("{}={}", field, trimmed))? {
+                (key, Some(value)) => {
+                    issue.extra.insert(key, value);
+                }
+                (key, None) => {
+                    issue.extra.remove(&key);
+                }
+            },
+        }
+    }
+    check_dates(issue.start, issue.due)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_cells() {
+        let config = Config::default();
+        let issue = Issue {
+            id: "001-002".to_string(),
+            title: "Quote \"me\", please".to_string(),
+            labels: vec!["bug".to_string(), "ui".to_string()],
+            comments: vec!["+++ a".to_string()],
+            ..Default::default()
+        };
+        let columns = columns(&[], &config).unwrap();
+        let text = write(&[&issue], &columns, b',', &config).unwrap();
+        assert_eq!(
+            text,
+            "id,parent,title,state,labels,comments\n001-002,001,\"Quote \"\"me\"\", please\",open,\"bug,ui\",1\n"
+        );
+
+        let rows = read(&text, b',', &config).unwrap();
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].value("parent"), Some("001"));
+        let mut copy = Issue::default();
+        apply(&mut copy, &rows[0], &config).unwrap();
+        assert_eq!(copy.title, issue.title);
+        assert_eq!(copy.labels, issue.labels);
+    }
+
+    #[test]
+    fn test_unknown_columns_rejected() {
+        let config = Config::default();
+        assert!(columns(&["id".to_string(), "owner".to_string()], &config).is_err());
+        assert!(read("id,owner\n001,me\n", b',', &config).is_err());
+    }
+}
@@ -4,74 +4,205 @@ use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use anyhow::{Context, Result};
+use crate::config::IdFormat;
+use crate::migrate;
 use crate::models::Issue;
 
 pub const STORAGE_DIR: &str = ".issues";
 
-/// Compute file path for an issue ID, nesting sub-issues in `parent/child.yaml`
-pub fn path_for(id: &str) -> PathBuf {
+/// Lock file held while a command needs exclusive access to the store
+///
+/// The file stays in place; holding means holding the OS lock on it, which the OS drops when
+/// the holder exits, even after a crash.
+pub const LOCK_FILE: &str = ".lock";
+
+/// How long to wait for another lock holder, unless `$GIT_ISSUE_LOCK_TIMEOUT` gives seconds
+const LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Exclusive lock on the store, released on drop
+pub struct StoreLock {
+    _file: fs::File,
+}
+
+/// Take the store lock, waiting for another holder up to the lock timeout
+pub fn lock() -> Result<StoreLock> {
+    let path = PathBuf::from(STORAGE_DIR).join(LOCK_FILE);
+    let timeout = match std::env::var("GIT_ISSUE_LOCK_TIMEOUT") {
+        Ok(secs) => secs
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(|s| std::time::Duration::try_from_secs_f64(s).ok())
+            .with_context(|| format!("invalid GIT_ISSUE_LOCK_TIMEOUT '{}' (expected seconds)", secs))?,
+        Err(_) => LOCK_TIMEOUT,
+    };
+    let deadline = std::time::Instant::now() + timeout;
+    // The lock file is local state; keep it out of commits of the store
+    let ignore = PathBuf::from(STORAGE_DIR).join(".gitignore");
+    if !ignore.exists() {
+        fs::write(&ignore, format!("{}\n", LOCK_FILE)).context("writing store .gitignore")?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .context("opening store lock")?;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(StoreLock { _file: file }),
+            Err(fs::TryLockError::WouldBlock) if std::time::Instant::now() <= deadline => {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+            Err(fs::TryLockError::WouldBlock) => anyhow::bail!("timed out waiting for {}", path.display()),
+            Err(fs::TryLockError::Error(e)) => return Err(e).context("locking the store"),
+        }
+    }
+}
+
+/// Compute file path for an issue ID, nesting sub-issues at any depth in `root/child.yaml`
+///
+/// Anything but an issue ID is refused, so no caller can reach a path outside the store.
+pub fn path_for(id: &str) -> Result<PathBuf> {
+    check_id(id)?;
     let mut path = PathBuf::from(STORAGE_DIR);
-    if let Some((parent, _)) = id.split_once('-') {
-        path.push(parent);
+    if let Some((root, _)) = id.split_once('-') {
+        path.push(root);
         path.push(format!("{}.yaml", id));
     } else {
         path.push(format!("{}.yaml", id));
     }
-    path
+    Ok(path)
+}
+
+/// Refuse anything that is not an issue ID, such as `../x`
+pub fn check_id(id: &str) -> Result<()> {
+    if !is_issue_id(id) {
+        anyhow::bail!("invalid issue ID '{}' (expected digits joined by '-', e.g. 001-002)", id);
+    }
+    Ok(())
 }
 
 /// Save issue back to storage, creating parent directory if needed
 pub fn save(issue: &Issue) -> Result<()> {
-    let path = path_for(&issue.id);
+    let path = path_for(&issue.id)?;
     if let Some(parent_dir) = path.parent() {
         fs::create_dir_all(parent_dir)?;
     }
     let mut file = fs::File::create(&path)
         .with_context(|| format!("Failed to save issue {}", issue.id))?;
-    let yaml = serde_yaml::to_string(issue)?;
+    let yaml = render(issue)?;
     file.write_all(yaml.as_bytes())?;
     Ok(())
 }
 
-/// Load issue from storage
+/// Serialize an issue as YAML stamped with the current schema version
+pub fn render(issue: &Issue) -> Result<String> {
+    let value = migrate::stamp(serde_yaml::to_value(issue)?);
+    Ok(serde_yaml::to_string(&value)?)
+}
+
+/// Load issue from storage, upgrading older schema versions in memory
 pub fn load(id: &str) -> Result<Issue> {
-    let path = path_for(id);
+    let path = path_for(id)?;
     let data =
         fs::read_to_string(&path).with_context(|| format!("Failed to read issue {}", id))?;
-    let issue: Issue = serde_yaml::from_str(&data)?;
+    parse(&data).with_context(|| format!("Failed to parse issue {}", id))
+}
+
+/// Parse issue YAML of any supported schema version
+pub fn parse(data: &str) -> Result<Issue> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(data)?;
+    migrate::upgrade(&mut value)?;
+    let issue: Issue = serde_yaml::from_value(value)?;
     Ok(issue)
 }
 
-/// Determine next root issue ID
-pub fn next_root_id() -> Result<String> {
-    let mut max_id = 0;
+/// Whether a name looks like an issue ID (`001`, `001-002`), as opposed to other store files
+pub fn is_issue_id(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Collect every issue ID in storage, roots and sub-issues alike, sorted
+pub fn all_ids() -> Result<Vec<String>> {
+    let mut ids = Vec::new();
     for entry in fs::read_dir(STORAGE_DIR)? {
-        let name = entry?.file_name().into_string().unwrap();
-        if let Some(base) = name.strip_suffix(".yaml") {
-            if base.len() == 3 && base.chars().all(|c| c.is_digit(10)) {
-                let v: usize = base.parse()?;
-                max_id = max_id.max(v);
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().into_string().unwrap();
+        if path.is_file() {
+            if let Some(id) = name.strip_suffix(".yaml").filter(|id| is_issue_id(id)) {
+                ids.push(id.to_string());
+            }
+        } else if path.is_dir() && is_issue_id(&name) {
+            for child_entry in fs::read_dir(&path)? {
+                let fname = child_entry?.file_name().into_string().unwrap();
+                if let Some(child_id) = fname.strip_suffix(".yaml").filter(|id| is_issue_id(id)) {
+                    ids.push(child_id.to_string());
+                }
             }
         }
     }
-    Ok(format!("{:03}", max_id + 1))
+    ids.sort();
+    Ok(ids)
 }
 
-/// Determine next sub-issue ID under given parent, scanning `.issues/{parent}`
-pub fn next_child_id(parent: &str) -> Result<String> {
-    let mut max_child = 0;
-    let dir = PathBuf::from(STORAGE_DIR).join(parent);
+/// Directory holding every sub-issue below the root of `id`, e.g. `.issues/001/` for `001-002`
+fn subtree_dir(id: &str) -> Result<PathBuf> {
+    check_id(id)?;
+    let root = id.split_once('-').map_or(id, |(root, _)| root);
+    Ok(PathBuf::from(STORAGE_DIR).join(root))
+}
+
+/// Direct sub-issue numbers of `id` found in its subtree directory, with their IDs
+fn direct_children(id: &str) -> Result<Vec<(usize, String)>> {
+    let dir = subtree_dir(id)?;
+    let prefix = format!("{}-", id);
+    let mut children = Vec::new();
     if dir.exists() {
         for entry in fs::read_dir(&dir)? {
             let name = entry?.file_name().into_string().unwrap();
-            if let Some(base) = name.strip_suffix(".yaml") {
-                if let Some(suffix) = base.strip_prefix(&format!("{}-", parent)) {
-                    if let Ok(v) = suffix.parse::<usize>() {
-                        max_child = max_child.max(v);
-                    }
-                }
+            let Some(child_id) = name.strip_suffix(".yaml") else { continue };
+            if let Some(n) = child_id.strip_prefix(&prefix).and_then(|s| s.parse::<usize>().ok()) {
+                children.push((n, child_id.to_string()));
+            }
+        }
+    }
+    Ok(children)
+}
+
+/// IDs of the direct sub-issues of `id`, sorted
+pub fn child_ids(id: &str) -> Result<Vec<String>> {
+    let mut ids: Vec<String> = direct_children(id)?.into_iter().map(|(_, id)| id).collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// Load every issue in storage, sorted by ID
+pub fn load_all() -> Result<Vec<Issue>> {
+    all_ids()?.iter().map(|id| load(id)).collect()
+}
+
+/// Determine next root issue ID
+pub fn next_root_id(format: &IdFormat) -> Result<String> {
+    let mut max_id = 0;
+    for entry in fs::read_dir(STORAGE_DIR)? {
+        let name = entry?.file_name().into_string().unwrap();
+        if let Some(base) = name.strip_suffix(".yaml") {
+            if !base.is_empty() && base.chars().all(|c| c.is_ascii_digit()) {
+                let v: usize = base.parse()?;
+                max_id = max_id.max(v);
             }
         }
     }
-    Ok(format!("{}-{:03}", parent, max_child + 1))
+    Ok(format.format(max_id + 1))
+}
+
+/// Determine next sub-issue ID under given parent, scanning its subtree directory
+pub fn next_child_id(parent: &str, format: &IdFormat) -> Result<String> {
+    let max_child = direct_children(parent)?.into_iter().map(|(n, _)| n).max().unwrap_or(0);
+    Ok(format!("{}-{}", parent, format.format(max_child + 1)))
 }
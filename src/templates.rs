@@ -0,0 +1,159 @@
+// Issue body templates stored in `.issues/templates/*.md`
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use crate::config::LabelRules;
+use crate::storage::STORAGE_DIR;
+
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// A markdown body with `{{placeholder}}`s and optional YAML front matter
+#[derive(Clone, Debug)]
+pub struct Template {
+    pub name: String,
+    /// Labels that pick this template when `create` is given none
+    pub labels: Vec<String>,
+    /// Sections the issue body must contain [default: every `##` heading]
+    pub required: Vec<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FrontMatter {
+    labels: Vec<String>,
+    required: Option<Vec<String>>,
+}
+
+fn dir() -> PathBuf {
+    PathBuf::from(STORAGE_DIR).join(TEMPLATES_DIR)
+}
+
+/// Headings in a markdown body, without their `#` markers, paired with their level
+fn headings(body: &str) -> impl Iterator<Item = (usize, &str)> {
+    body.lines().filter_map(|line| {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        let text = line[level..].strip_prefix(' ')?;
+        (level > 0).then_some((level, text.trim()))
+    })
+}
+
+impl Template {
+    fn parse(name: &str, data: &str) -> Result<Template> {
+        let (meta, body) = match data.strip_prefix("---\n").and_then(|rest| rest.split_once("\n---\n")) {
+            Some((front, body)) => (serde_yaml::from_str::<Option<FrontMatter>>(front)?.unwrap_or_default(), body),
+            None => (FrontMatter::default(), data),
+        };
+        let required = meta.required.unwrap_or_else(|| {
+            headings(body).filter(|(level, _)| *level == 2).map(|(_, text)| text.to_string()).collect()
+        });
+        Ok(Template {
+            name: name.to_string(),
+            labels: meta.labels,
+            required,
+            body: body.to_string(),
+        })
+    }
+
+    /// Fill in `{{name}}` placeholders; unknown placeholders are left as they are
+    pub fn render(&self, vars: &[(&str, &str)]) -> String {
+        vars.iter().fold(self.body.clone(), |body, (key, value)| {
+            body.replace(&format!("{{{{{}}}}}", key), value)
+        })
+    }
+
+    /// Reject content that lacks any of the required sections
+    pub fn check(&self, content: &str) -> Result<()> {
+        let missing: Vec<&str> = self
+            .required
+            .iter()
+            .filter(|section| !headings(content).any(|(_, text)| text.eq_ignore_ascii_case(section)))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            bail!("content is missing section(s) required by template '{}': {}", self.name, missing.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Body for a new issue: the rendered template when no content is given, otherwise the checked content
+    pub fn apply(&self, content: &str, vars: &[(&str, &str)]) -> Result<String> {
+        if content.trim().is_empty() {
+            return Ok(self.render(vars));
+        }
+        self.check(content)?;
+        Ok(content.to_string())
+    }
+}
+
+/// Load every template, sorted by name
+pub fn all() -> Result<Vec<Template>> {
+    let mut names = Vec::new();
+    if dir().exists() {
+        for entry in fs::read_dir(dir())? {
+            let file = entry?.file_name().into_string().unwrap();
+            if let Some(name) = file.strip_suffix(".md") {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.iter().map(|name| load(name)).collect()
+}
+
+/// Reject names that would reach outside the templates directory
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        bail!("invalid template name '{}'", name);
+    }
+    Ok(())
+}
+
+/// Load a template by name
+pub fn load(name: &str) -> Result<Template> {
+    validate_name(name)?;
+    let path = dir().join(format!("{}.md", name));
+    if !path.exists() {
+        let names: Vec<String> = all()?.into_iter().map(|t| t.name).collect();
+        bail!("unknown template '{}' (templates: {})", name, names.join(", "));
+    }
+    let data = fs::read_to_string(&path).with_context(|| format!("Failed to read template {}", name))?;
+    Template::parse(name, &data).with_context(|| format!("Failed to parse template {}", name))
+}
+
+/// Template for a new issue: the named one, none for `none`, or the first whose labels match
+pub fn select(name: Option<&str>, labels: &[String], rules: &LabelRules) -> Result<Option<Template>> {
+    match name {
+        Some("none") => Ok(None),
+        Some(name) => load(name).map(Some),
+        None => Ok(all()?
+            .into_iter()
+            .find(|t| t.labels.iter().any(|l| labels.iter().any(|label| rules.matches(l, label))))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUG: &str = "---\nlabels: [bug]\n---\n# {{title}}\n\n## Description\n\n## Steps to Reproduce\n";
+
+    #[test]
+    fn test_required_sections_default_to_level_two_headings() {
+        let template = Template::parse("bug", BUG).unwrap();
+        assert_eq!(template.labels, vec!["bug"]);
+        assert_eq!(template.required, vec!["Description", "Steps to Reproduce"]);
+        assert!(template.check("## description\ntext\n## Steps to Reproduce\n").is_ok());
+        assert!(template.check("## Description\n").is_err());
+    }
+
+    #[test]
+    fn test_apply_renders_only_without_content() {
+        let template = Template::parse("bug", BUG).unwrap();
+        let body = template.apply("", &[("title", "Crash")]).unwrap();
+        assert!(body.starts_with("# Crash\n"));
+        assert!(template.apply("just words", &[]).is_err());
+    }
+}
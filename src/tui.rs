@@ -0,0 +1,437 @@
+// Full-screen interface for browsing and triaging issues
+
+use crate::commands;
+use crate::config::{Config, Workflow};
+use crate::logging::{append_log, edit_entry, log_cascade};
+use crate::models::{parent_of, Issue};
+use crate::render::Renderer;
+use crate::storage::load_all;
+use anyhow::{bail, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::io::IsTerminal;
+
+const HELP: &str = "j/k move  / filter  c comment  x close  o reopen  l label  m move  a closed  r reload  q quit";
+
+/// What the footer is collecting text for
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Prompt {
+    Filter,
+    Comment,
+    Close,
+    Reopen,
+    Label,
+    Move,
+}
+
+impl Prompt {
+    fn label(self) -> &'static str {
+        match self {
+            Prompt::Filter => "filter",
+            Prompt::Comment => "comment",
+            Prompt::Close => "close message",
+            Prompt::Reopen => "reopen message",
+            Prompt::Label => "labels (+add -remove)",
+            Prompt::Move => "move to state",
+        }
+    }
+}
+
+struct App {
+    config: Config,
+    issues: Vec<Issue>,
+    show_closed: bool,
+    filter: String,
+    /// Tree rows: depth and index into `issues`
+    rows: Vec<(usize, usize)>,
+    list: ListState,
+    scroll: u16,
+    prompt: Option<(Prompt, String)>,
+    status: String,
+    /// Set by `submit` after each action, so the next frame is painted from scratch in case
+    /// anything reached the terminal around `act`'s capture, such as a subprocess's stderr
+    redraw: bool,
+    quit: bool,
+}
+
+/// Run `git issue tui` until the user quits
+pub fn run() -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        bail!("tui needs an interactive terminal");
+    }
+    let mut app = App::new()?;
+    let mut terminal = ratatui::try_init()?;
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn new() -> Result<App> {
+        let mut app = App {
+            config: Config::load()?,
+            issues: Vec::new(),
+            show_closed: false,
+            filter: String::new(),
+            rows: Vec::new(),
+            list: ListState::default(),
+            scroll: 0,
+            prompt: None,
+            status: String::new(),
+            redraw: false,
+            quit: false,
+        };
+        app.reload()?;
+        Ok(app)
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            if std::mem::take(&mut self.redraw) {
+                terminal.clear()?;
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-read every issue, keeping the selection on the same issue when it is still shown
+    fn reload(&mut self) -> Result<()> {
+        let selected = self.selected().map(|i| i.id.clone());
+        self.config = Config::load()?;
+        self.issues = load_all()?;
+        self.issues.sort_by(|a, b| a.id.cmp(&b.id));
+        self.refilter(selected.as_deref());
+        Ok(())
+    }
+
+    fn refilter(&mut self, keep: Option<&str>) {
+        self.rows = visible(&self.issues, &self.filter, self.show_closed, &self.config.workflow);
+        let at = keep.and_then(|id| self.rows.iter().position(|&(_, n)| self.issues[n].id == id));
+        self.list.select(at.or(if self.rows.is_empty() { None } else { Some(0) }));
+        self.scroll = 0;
+    }
+
+    fn selected(&self) -> Option<&Issue> {
+        self.list.selected().and_then(|row| self.rows.get(row)).map(|&(_, n)| &self.issues[n])
+    }
+
+    fn key(&mut self, key: KeyEvent) {
+        if let Some((prompt, mut input)) = self.prompt.take() {
+            match key.code {
+                KeyCode::Enter => self.submit(prompt, input.trim()),
+                KeyCode::Esc if prompt == Prompt::Filter => {
+                    self.filter.clear();
+                    self.refilter(None);
+                }
+                KeyCode::Esc => {}
+                code => {
+                    match code {
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Char(c) => input.push(c),
+                        _ => {}
+                    }
+                    // Filtering is live: the tree follows every keystroke
+                    if prompt == Prompt::Filter {
+                        let selected = self.selected().map(|i| i.id.clone());
+                        self.filter = input.clone();
+                        self.refilter(selected.as_deref());
+                    }
+                    self.prompt = Some((prompt, input));
+                }
+            }
+            return;
+        }
+
+        self.status.clear();
+        let last = self.rows.len().saturating_sub(1);
+        let row = self.list.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.refilter(None);
+            }
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('j') | KeyCode::Down => self.select(row.saturating_add(1).min(last)),
+            KeyCode::Char('k') | KeyCode::Up => self.select(row.saturating_sub(1)),
+            KeyCode::Char('g') | KeyCode::Home => self.select(0),
+            KeyCode::Char('G') | KeyCode::End => self.select(last),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Char('a') => {
+                self.show_closed = !self.show_closed;
+                let selected = self.selected().map(|i| i.id.clone());
+                self.refilter(selected.as_deref());
+            }
+            KeyCode::Char('r') => self.after(Ok("reloaded".to_string())),
+            KeyCode::Char('/') => self.prompt = Some((Prompt::Filter, self.filter.clone())),
+            KeyCode::Char(c) if self.selected().is_some() => {
+                let prompt = match c {
+                    'c' => Prompt::Comment,
+                    'x' => Prompt::Close,
+                    'o' => Prompt::Reopen,
+                    'l' => Prompt::Label,
+                    'm' => Prompt::Move,
+                    _ => return,
+                };
+                self.prompt = Some((prompt, String::new()));
+            }
+            _ => {}
+        }
+    }
+
+    fn select(&mut self, row: usize) {
+        if !self.rows.is_empty() {
+            self.list.select(Some(row));
+            self.scroll = 0;
+        }
+    }
+
+    fn submit(&mut self, prompt: Prompt, input: &str) {
+        if prompt == Prompt::Filter {
+            return;
+        }
+        let Some(id) = self.selected().map(|i| i.id.clone()) else {
+            return;
+        };
+        if input.is_empty() {
+            self.status = format!("{} is empty, nothing done", prompt.label());
+            return;
+        }
+        let result = act(prompt, &id, input);
+        self.redraw = true;
+        self.after(result);
+    }
+
+    /// Show the outcome of an action and pick up its changes
+    fn after(&mut self, result: Result<String>) {
+        self.status = match result.and_then(|done| self.reload().map(|_| done)) {
+            Ok(done) => done,
+            Err(err) => format!("error: {:#}", err),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main);
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|&(depth, n)| {
+                let issue = &self.issues[n];
+                let item = ListItem::new(format!("{}{} {}", "  ".repeat(depth), issue.id, issue.title));
+                match self.config.workflow.is_terminal(&issue.state) {
+                    true => item.style(Style::new().add_modifier(Modifier::DIM)),
+                    false => item,
+                }
+            })
+            .collect();
+        let mut title = format!("Issues ({})", if self.show_closed { "all" } else { "open" });
+        if !self.filter.is_empty() {
+            title.push_str(&format!(" /{}", self.filter));
+        }
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, left, &mut self.list);
+
+        let detail = match self.selected() {
+            Some(issue) => commands::view_lines(issue, &self.issues, &self.config, Some(&Renderer { color: false })).join("\n"),
+            None => "no matching issues".to_string(),
+        };
+        let detail = Paragraph::new(detail)
+            .block(Block::default().borders(Borders::ALL).title("Detail"))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(detail, right);
+
+        let line = match &self.prompt {
+            Some((prompt, input)) => format!("{}: {}", prompt.label(), input),
+            None if !self.status.is_empty() => self.status.clone(),
+            None => HELP.to_string(),
+        };
+        frame.render_widget(Paragraph::new(line), footer);
+    }
+}
+
+/// Run one triage action under the store lock, returning what it printed (the terminal is not ours to print to) or a summary
+fn act(prompt: Prompt, id: &str, input: &str) -> Result<String> {
+    let _lock = crate::storage::lock()?;
+    let (result, printed) = crate::output::capture(|| triage(prompt, id, input));
+    let done = result?;
+    let lines: Vec<&str> = printed.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    Ok(if lines.is_empty() { done } else { lines.join(" · ") })
+}
+
+/// Apply one triage action through `commands`, with the same audit entries as the CLI
+fn triage(prompt: Prompt, id: &str, input: &str) -> Result<String> {
+    match prompt {
+        Prompt::Filter => Ok(String::new()),
+        Prompt::Comment => {
+            commands::comment(id, input)?;
+            append_log(&format!("COMMENT id={} msg={}", id, input))?;
+            Ok(format!("{} commented", id))
+        }
+        Prompt::Close => {
+            let parents = commands::close(id, input, None, None)?;
+            append_log(&format!("CLOSE id={} msg={}", id, input))?;
+            log_cascade(id, &parents)?;
+            Ok(format!("{} closed", id))
+        }
+        Prompt::Reopen => {
+            commands::reopen(id, input)?;
+            append_log(&format!("REOPEN id={} msg={}", id, input))?;
+            Ok(format!("{} reopened", id))
+        }
+        Prompt::Label => {
+            let (add_label, remove_label) = label_changes(input);
+            let args = crate::EditArgs {
+                id: id.to_string(),
+                title: None,
+                content: None,
+                due: None,
+                start: None,
+                field: Vec::new(),
+                add_label,
+                remove_label,
+            };
+            let issue = commands::edit(args.clone())?;
+            append_log(&edit_entry(&args, &issue))?;
+            Ok(format!("{} labels: {}", id, issue.labels.join(",")))
+        }
+        Prompt::Move => {
+            let (state, parents) = commands::status(id, input, None, None, None)?;
+            append_log(&format!("STATUS id={} state={} msg=", id, state))?;
+            log_cascade(id, &parents)?;
+            Ok(format!("{} moved to {}", id, state))
+        }
+    }
+}
+
+/// Split `bug +ui -wontfix` into labels to add and labels to remove
+fn label_changes(input: &str) -> (Vec<String>, Vec<String>) {
+    let (mut add, mut remove) = (Vec::new(), Vec::new());
+    for word in input.split([' ', ',']).filter(|w| !w.is_empty()) {
+        match word.strip_prefix('-') {
+            Some(label) => remove.push(label.to_string()),
+            None => add.push(word.trim_start_matches('+').to_string()),
+        }
+    }
+    (add, remove)
+}
+
+/// Tree rows for issues matching `filter` (open ones unless `show_closed`), keeping ancestors of matches
+fn visible(issues: &[Issue], filter: &str, show_closed: bool, workflow: &Workflow) -> Vec<(usize, usize)> {
+    let needle = filter.to_lowercase();
+    let matches = |issue: &Issue| {
+        (show_closed || !workflow.is_terminal(&issue.state))
+            && (issue.id.contains(&needle)
+                || issue.title.to_lowercase().contains(&needle)
+                || issue.labels.iter().any(|l| l.to_lowercase().contains(&needle)))
+    };
+    let mut keep: HashSet<&str> = HashSet::new();
+    for issue in issues.iter().filter(|i| matches(i)) {
+        let mut id = Some(issue.id.as_str());
+        while let Some(current) = id {
+            keep.insert(current);
+            id = parent_of(current);
+        }
+    }
+    issues
+        .iter()
+        .enumerate()
+        .filter(|(_, issue)| keep.contains(issue.id.as_str()))
+        .map(|(n, issue)| (issue.id.matches('-').count(), n))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::State;
+
+    fn issue(id: &str, title: &str, state: &str) -> Issue {
+        Issue { id: id.to_string(), title: title.to_string(), state: State::from(state), ..Default::default() }
+    }
+
+    #[test]
+    fn test_visible_keeps_ancestors() {
+        let issues = vec![
+            issue("001", "Epic", "open"),
+            issue("001-001", "Login page", "open"),
+            issue("001-002", "Signup", "closed"),
+            issue("002", "Other", "open"),
+        ];
+        let workflow = Workflow::default();
+        let ids = |rows: Vec<(usize, usize)>| rows.iter().map(|&(d, n)| (d, issues[n].id.as_str())).collect::<Vec<_>>();
+        assert_eq!(ids(visible(&issues, "", false, &workflow)), vec![(0, "001"), (1, "001-001"), (0, "002")]);
+        assert_eq!(ids(visible(&issues, "LOGIN", false, &workflow)), vec![(0, "001"), (1, "001-001")]);
+        assert_eq!(ids(visible(&issues, "sign", true, &workflow)), vec![(0, "001"), (1, "001-002")]);
+    }
+
+    #[test]
+    fn test_act_captures_output_and_logs() {
+        // Commands work on the store in the working directory, so the test body runs in a child
+        // test process inside a fresh store, with its own config and author
+        const CHILD: &str = "GIT_ISSUE_TUI_ACT_TEST";
+        if std::env::var_os(CHILD).is_none() {
+            let temp = tempfile::tempdir().unwrap();
+            let output = std::process::Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", "tui::tests::test_act_captures_output_and_logs", "--nocapture"])
+                .current_dir(&temp)
+                .env(CHILD, "1")
+                .env("XDG_CONFIG_HOME", temp.path().join("xdg"))
+                .env("GIT_ISSUE_AUTHOR", "alice")
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+            return;
+        }
+
+        std::fs::create_dir_all(crate::storage::STORAGE_DIR).unwrap();
+        let parent = Issue { auto_close: Some(true), ..issue("001", "Epic", "open") };
+        for issue in [parent, issue("001-001", "Login page", "open")] {
+            crate::storage::save(&issue).unwrap();
+        }
+
+        assert_eq!(act(Prompt::Comment, "001-001", "looking").unwrap(), "001-001 | +++ looking");
+        assert_eq!(act(Prompt::Label, "001-001", "bug ui").unwrap(), "001-001 | Login page - bug,ui");
+        assert_eq!(
+            act(Prompt::Close, "001-001", "fixed").unwrap(),
+            "001-001 | >>> fixed · 001 | >>> all sub-issues closed (last: 001-001)"
+        );
+        assert!(act(Prompt::Reopen, "001-001", "again").is_err());
+
+        let log = std::fs::read_to_string(std::path::Path::new(crate::storage::STORAGE_DIR).join(crate::logging::AUDIT_LOG)).unwrap();
+        assert_eq!(
+            log.lines().collect::<Vec<_>>(),
+            vec![
+                "COMMENT id=001-001 msg=looking author=alice",
+                "EDIT id=001-001 labels=bug,ui author=alice",
+                "CLOSE id=001-001 msg=fixed author=alice",
+                "CLOSE id=001 msg=all sub-issues closed cascade=001-001 author=alice",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_label_changes() {
+        let (add, remove) = label_changes("bug +ui, -wontfix");
+        assert_eq!(add, vec!["bug", "ui"]);
+        assert_eq!(remove, vec!["wontfix"]);
+    }
+}
@@ -0,0 +1,58 @@
+// End-to-end tests for auto-closing parents
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_auto_close_per_issue() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+
+    git_issue(&temp).args(["create", "-t", "Epic", "-c", "body", "--auto-close"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Manual", "-c", "body"]).assert().success();
+    for parent in ["001", "001", "002"] {
+        git_issue(&temp).args(["create", "-p", parent, "-t", "Task", "-c", "body"]).assert().success();
+    }
+
+    // Parent stays open while a sub-issue is pending
+    git_issue(&temp).args(["close", "001-001", "-m", "done"])
+        .assert().success().stdout(predicate::str::contains("all sub-issues closed").not());
+    git_issue(&temp).args(["close", "001-002", "-m", "done"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | >>> all sub-issues closed (last: 001-002)"));
+    git_issue(&temp).args(["ls", "--state", "closed"])
+        .assert().success().stdout(predicate::str::contains("001 | Epic [closed]"));
+
+    // Issues without the opt-in are left alone
+    git_issue(&temp).args(["close", "002-001", "-m", "done"]).assert().success();
+    git_issue(&temp).arg("ls")
+        .assert().success().stdout(predicate::str::contains("002 | Manual\n"));
+
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("CLOSE id=001 msg=all sub-issues closed cascade=001-002"));
+}
+
+#[test]
+fn test_auto_close_from_config_with_opt_out() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+    git_issue(&temp).args(["config", "set", "workflow.auto_close_parents", "true"]).assert().success();
+
+    let plan = r#"{"title": "Planned", "content": "body", "sub_issues": [{"title": "Only", "content": "body"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Kept", "-c", "body", "--auto-close", "false"]).assert().success();
+    git_issue(&temp).args(["create", "-p", "002", "-t", "Task", "-c", "body"]).assert().success();
+
+    git_issue(&temp).args(["status", "001-001", "closed", "-m", "done"])
+        .assert().success().stdout(predicate::str::contains("001 | >>> all sub-issues closed"));
+    git_issue(&temp).args(["close", "002-001", "-m", "done"]).assert().success();
+    git_issue(&temp).arg("ls")
+        .assert().success()
+        .stdout(predicate::str::contains("002 | Kept\n"))
+        .stdout(predicate::str::contains("Planned").not());
+}
@@ -0,0 +1,80 @@
+// End-to-end tests for `git issue board`
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+const CONFIG: &str = "workflow:
+  states:
+    - name: todo
+    - name: doing
+    - name: done
+      terminal: true
+";
+
+fn seed(temp: &tempfile::TempDir) {
+    let issues_dir = temp.path().join(".issues");
+    fs::create_dir_all(&issues_dir).unwrap();
+    fs::write(issues_dir.join("config.yaml"), CONFIG).unwrap();
+    git_issue(temp).args(["create", "-t", "Login fails on Safari", "-c", "body", "--label", "bug"]).assert().success();
+    git_issue(temp).args(["create", "-t", "Dark mode", "-c", "body"]).assert().success();
+    git_issue(temp).args(["create", "-t", "Old chore", "-c", "body"]).assert().success();
+    git_issue(temp).args(["status", "002", "doing"]).assert().success();
+    git_issue(temp).args(["assign", "002", "alice"]).assert().success();
+    git_issue(temp).args(["close", "003", "-m", "done"]).assert().success();
+}
+
+#[test]
+fn test_board_plain_layout_when_piped() {
+    let temp = setup_temp_dir();
+    seed(&temp);
+
+    git_issue(&temp).arg("board")
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "== todo (1) ==
+001 | Login fails on Safari - bug
+
+== doing (1) ==
+002 | Dark mode @alice
+",
+        ));
+    git_issue(&temp).args(["board", "--by", "assignee", "--state", "all"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "== @alice (1) ==
+002 | Dark mode @alice
+
+== (none) (2) ==
+001 | Login fails on Safari - bug
+003 | Old chore
+",
+        ));
+    git_issue(&temp).args(["board", "--by", "owner"])
+        .assert().failure().stderr(predicate::str::contains("unknown field 'owner'"));
+}
+
+#[test]
+fn test_board_columns_fit_width() {
+    let temp = setup_temp_dir();
+    seed(&temp);
+
+    git_issue(&temp).args(["board", "--state", "all", "--width", "60"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "todo (1)           │ doing (1)          │ done (1)
+────────────────── │ ────────────────── │ ──────────────────
+001                │ 002 @alice         │ 003
+Login fails on     │ Dark mode          │ Old chore
+Safari             │                    │
+bug                │                    │
+",
+        ));
+    // Too narrow for three columns
+    git_issue(&temp).args(["board", "--state", "all", "--width", "40"])
+        .assert().success()
+        .stdout(predicate::str::starts_with("== todo (1) =="));
+}
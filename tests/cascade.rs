@@ -0,0 +1,45 @@
+// End-to-end tests for recursive closes
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_close_recursive_requires_confirmation() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+
+    git_issue(&temp).args(["create", "-t", "Epic", "-c", "body"]).assert().success();
+    for title in ["Task A", "Task B", "Task C"] {
+        git_issue(&temp).args(["create", "-p", "001", "-t", title, "-c", "body"]).assert().success();
+    }
+    git_issue(&temp).args(["close", "001-002", "-m", "done early"]).assert().success();
+
+    // Without --yes only the affected issues are listed
+    git_issue(&temp).args(["close", "001", "-m", "epic done", "--recursive"])
+        .assert().failure()
+        .stdout(predicate::str::contains("closing 3 issue(s): 001-003, 001-001, 001"))
+        .stderr(predicate::str::contains("re-run with --yes"));
+    git_issue(&temp).arg("ls")
+        .assert().success().stdout(predicate::str::contains("001-001 | Task A\n"));
+
+    git_issue(&temp).args(["close", "001", "-m", "epic done", "--recursive", "--yes", "--reason", "wontfix"])
+        .assert().success()
+        .stdout(predicate::str::contains("001-003 | >>> epic done"))
+        .stdout(predicate::str::contains("001 | >>> epic done"));
+    git_issue(&temp).args(["ls", "--state", "all"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | Epic [closed: wontfix]"))
+        .stdout(predicate::str::contains("001-001 | Task A [closed: wontfix]"))
+        .stdout(predicate::str::contains("001-002 | Task B [closed]\n"));
+
+    // One audit entry per closed issue
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("CLOSE id=001-001 msg=epic done reason=wontfix recursive=001"))
+        .stdout(predicate::str::contains("CLOSE id=001 msg=epic done reason=wontfix recursive=001"))
+        .stdout(predicate::str::contains("CLOSE id=001-002 msg=epic done").not());
+}
@@ -0,0 +1,53 @@
+// End-to-end tests for checklist items and the close rule
+
+use predicates::prelude::*;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+const CONTENT: &str = "## Acceptance Criteria\n- [ ] parser\n- [x] docs\n- [ ] tests\n";
+
+#[test]
+fn test_check_and_uncheck_items() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).args(["create", "-t", "Feature", "-c", CONTENT]).assert().success();
+    git_issue(&temp).args(["ls"]).assert().success().stdout(predicate::str::contains("001 | Feature [1/3]"));
+
+    git_issue(&temp).args(["check", "001", "1", "3"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | checklist 3/3"));
+    git_issue(&temp).args(["uncheck", "001", "2"]).assert().success();
+    git_issue(&temp).args(["check", "001", "4"])
+        .assert().failure()
+        .stderr(predicate::str::contains("no checklist item 4 (issue has 3)"));
+    git_issue(&temp).args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | Feature [2/3]"))
+        .stdout(predicate::str::contains("- [x] parser\n- [ ] docs\n- [x] tests"));
+
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("CHECK id=001 items=1,3 progress=3/3"))
+        .stdout(predicate::str::contains("UNCHECK id=001 items=2 progress=2/3"));
+}
+
+#[test]
+fn test_require_checklist_blocks_completed_close() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).args(["config", "set", "workflow.require_checklist", "true"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Feature", "-c", CONTENT]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Sub", "--content=- [ ] step", "-p", "001"]).assert().success();
+
+    git_issue(&temp).args(["close", "001-001", "-m", "done"])
+        .assert().failure()
+        .stderr(predicate::str::contains("1 checklist item(s) still unchecked"));
+    git_issue(&temp).args(["close", "001", "-m", "done", "-r", "-y"])
+        .assert().failure()
+        .stderr(predicate::str::contains("has 1 unchecked checklist item(s)"));
+
+    // Dropping work is not blocked
+    git_issue(&temp).args(["close", "001-001", "-m", "not needed", "--reason", "wontfix"]).assert().success();
+    git_issue(&temp).args(["check", "001", "1", "3"]).assert().success();
+    git_issue(&temp).args(["close", "001", "-m", "done"]).assert().success();
+}
@@ -0,0 +1,91 @@
+// End-to-end tests for assignees and claims
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue_as as git_issue, hold_store_lock, setup_temp_dir};
+
+#[test]
+fn test_assign_and_filters() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+    git_issue(&temp, "ada").args(["create", "-t", "Mine", "-c", "body"]).assert().success();
+    git_issue(&temp, "ada").args(["create", "-t", "Free", "-c", "body"]).assert().success();
+
+    git_issue(&temp, "ada").args(["assign", "001", "@me", "bob"])
+        .assert().success().stdout("001 | assigned to ada, bob\n");
+    git_issue(&temp, "ada").args(["ls", "--assignee", "@me"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | Mine [@ada,@bob]"))
+        .stdout(predicate::str::contains("Free").not());
+    git_issue(&temp, "ada").args(["ls", "--unassigned"])
+        .assert().success()
+        .stdout(predicate::str::contains("002 | Free"))
+        .stdout(predicate::str::contains("Mine").not());
+
+    git_issue(&temp, "ada").args(["assign", "001", "--remove", "bob"])
+        .assert().success().stdout("001 | assigned to ada\n");
+}
+
+#[test]
+fn test_claim_skips_held_issues_and_releases_expired() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+    for title in ["First", "Second"] {
+        git_issue(&temp, "ada").args(["create", "-t", title, "-c", "body"]).assert().success();
+    }
+
+    git_issue(&temp, "agent-1").arg("claim")
+        .assert().success().stdout(predicate::str::contains("001 | claimed by agent-1"));
+    git_issue(&temp, "agent-2").args(["claim", "--lease", "0s"])
+        .assert().success().stdout(predicate::str::contains("002 | claimed by agent-2"));
+    git_issue(&temp, "agent-2").args(["claim", "001"])
+        .assert().failure()
+        .stdout(predicate::str::contains("002 | claim by agent-2 expired"))
+        .stderr(predicate::str::contains("already claimed by agent-1"));
+
+    // agent-2's lease has lapsed, so it is released and can be taken again
+    git_issue(&temp, "agent-3").arg("claim")
+        .assert().success().stdout(predicate::str::contains("002 | claimed by agent-3"));
+    git_issue(&temp, "agent-4").arg("claim")
+        .assert().success().stdout(predicate::str::contains("no unclaimed issues"));
+    git_issue(&temp, "ada").args(["view", "002"])
+        .assert().success()
+        .stdout(predicate::str::contains("[@agent-3]"))
+        .stdout(predicate::str::contains("claimed by agent-3 until"));
+
+    git_issue(&temp, "agent-1").args(["release", "001"]).assert().success();
+    git_issue(&temp, "ada").args(["ls", "--unassigned"])
+        .assert().success().stdout(predicate::str::contains("001 | First"));
+    // Nothing holds the lock once commands are done
+    std::fs::File::open(temp.path().join(".issues/.lock")).unwrap().try_lock().unwrap();
+    assert_eq!(std::fs::read_to_string(temp.path().join(".issues/.gitignore")).unwrap(), ".lock\n");
+
+    git_issue(&temp, "ada").arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("CLAIM id=001 by=agent-1 until="))
+        .stdout(predicate::str::contains("RELEASE id=002 by=agent-2 expired=true"))
+        .stdout(predicate::str::contains("RELEASE id=001 by=agent-1 author=agent-1"));
+}
+
+#[test]
+fn test_writes_wait_for_the_store_lock() {
+    let temp = setup_temp_dir();
+    git_issue(&temp, "ada").args(["create", "-t", "First", "-c", "body"]).assert().success();
+    let held = hold_store_lock(&temp);
+
+    git_issue(&temp, "ada").args(["comment", "001", "-m", "late"])
+        .env("GIT_ISSUE_LOCK_TIMEOUT", "0.2")
+        .assert().failure().stderr(predicate::str::contains("timed out waiting"));
+    git_issue(&temp, "ada").args(["view", "001"])
+        .assert().success().stdout(predicate::str::contains("late").not());
+    git_issue(&temp, "ada").args(["comment", "001", "-m", "late"])
+        .env("GIT_ISSUE_LOCK_TIMEOUT", "soon")
+        .assert().failure().stderr(predicate::str::contains("invalid GIT_ISSUE_LOCK_TIMEOUT 'soon'"));
+
+    // A lock file nobody holds, as a crashed process leaves it, does not get in the way
+    drop(held);
+    git_issue(&temp, "ada").args(["comment", "001", "-m", "late"]).assert().success();
+}
@@ -0,0 +1,60 @@
+// End-to-end tests for close reasons, `next` and `stats`
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_close_reasons_and_progress() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+
+    git_issue(&temp).args(["create", "-t", "Epic", "-c", "body"]).assert().success();
+    for title in ["Done task", "Dropped task", "Copy task", "Open task"] {
+        git_issue(&temp).args(["create", "-p", "001", "-t", title, "-c", "body"]).assert().success();
+    }
+
+    git_issue(&temp).args(["close", "001-001", "-m", "shipped"]).assert().success();
+    git_issue(&temp).args(["close", "001-002", "-m", "not needed", "--reason", "wontfix"])
+        .assert().success();
+
+    // Duplicate closes need a valid original
+    git_issue(&temp).args(["close", "001-003", "-m", "dup", "--reason", "duplicate"])
+        .assert().failure().stderr(predicate::str::contains("--duplicate-of is required"));
+    git_issue(&temp).args(["close", "001-003", "-m", "dup", "--duplicate-of", "001-003"])
+        .assert().failure().stderr(predicate::str::contains("cannot duplicate itself"));
+    git_issue(&temp).args(["close", "001-003", "-m", "dup", "--duplicate-of", "001-001"])
+        .assert().success();
+
+    git_issue(&temp).args(["ls", "--state", "all"])
+        .assert().success()
+        .stdout(predicate::str::contains("001-001 | Done task [closed]\n"))
+        .stdout(predicate::str::contains("001-002 | Dropped task [closed: wontfix]"))
+        .stdout(predicate::str::contains("001-003 | Copy task [closed: duplicate of 001-001]"));
+    git_issue(&temp).args(["view", "001-002"])
+        .assert().success().stdout(predicate::str::contains("[closed: wontfix]"));
+
+    // Only completed closures count towards progress
+    git_issue(&temp).arg("next")
+        .assert().success()
+        .stdout(predicate::str::contains("001-004 | Open task"))
+        .stdout(predicate::str::contains("progress: 1/2 (001)"));
+    git_issue(&temp).args(["stats", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains("wontfix: 1"))
+        .stdout(predicate::str::contains("duplicate: 1"))
+        .stdout(predicate::str::contains("progress: 1/2 (50%)"));
+
+    // Reopening clears the reason
+    git_issue(&temp).args(["reopen", "001-002", "-m", "needed after all"]).assert().success();
+    git_issue(&temp).args(["ls"])
+        .assert().success().stdout(predicate::str::contains("001-002 | Dropped task\n"));
+
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("CLOSE id=001-002 msg=not needed reason=wontfix"))
+        .stdout(predicate::str::contains("reason=duplicate duplicate_of=001-001"));
+}
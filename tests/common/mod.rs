@@ -0,0 +1,29 @@
+// Fixtures shared by the end-to-end tests; each test file uses a different subset
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+
+pub fn setup_temp_dir() -> tempfile::TempDir {
+    tempfile::tempdir().expect("failed to create temp dir")
+}
+
+/// `git-issue` run in `temp`, with the user config kept inside it
+pub fn git_issue(temp: &tempfile::TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("git-issue").unwrap();
+    cmd.current_dir(temp).env("XDG_CONFIG_HOME", temp.path().join("xdg"));
+    cmd
+}
+
+/// `git_issue` acting as `author`
+pub fn git_issue_as(temp: &tempfile::TempDir, author: &str) -> Command {
+    let mut cmd = git_issue(temp);
+    cmd.env("GIT_ISSUE_AUTHOR", author);
+    cmd
+}
+
+/// Hold the store lock in `temp` as another process would, until the file is dropped
+pub fn hold_store_lock(temp: &tempfile::TempDir) -> std::fs::File {
+    let file = std::fs::File::create(temp.path().join(".issues/.lock")).unwrap();
+    file.lock().unwrap();
+    file
+}
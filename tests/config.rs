@@ -0,0 +1,67 @@
+// End-to-end tests for repository and user configuration
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_config_get_set_and_ls_defaults() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+
+    git_issue(&temp).args(["create", "-t", "First", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Second", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["close", "001", "-m", "done"]).assert().success();
+
+    // Built-in defaults are reported by `get`
+    git_issue(&temp).args(["config", "get", "ls.state"])
+        .assert().success().stdout("open\n");
+
+    // Config file is not mistaken for an issue
+    git_issue(&temp).args(["config", "set", "ls.state", "all"]).assert().success();
+    git_issue(&temp).args(["config", "set", "ls.order", "desc"]).assert().success();
+    assert!(temp.path().join(".issues/config.yaml").exists());
+
+    let out = git_issue(&temp).arg("ls").assert().success().get_output().stdout.clone();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.find("002 | Second").unwrap() < out.find("001 | First [closed]").unwrap());
+
+    // Flags still win over config
+    git_issue(&temp).args(["ls", "--state", "open"])
+        .assert().success().stdout(predicate::str::contains("First").not());
+
+    // Unknown keys are rejected
+    git_issue(&temp).args(["config", "set", "ls.colour", "red"])
+        .assert().failure().stderr(predicate::str::contains("Invalid value for ls.colour"));
+
+    git_issue(&temp).arg("log")
+        .assert().success().stdout(predicate::str::contains("CONFIG scope=repo key=ls.state value=all"));
+}
+
+#[test]
+fn test_user_config_id_width_and_allowed_labels() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+
+    // User-level settings apply, and the repo file overrides them
+    git_issue(&temp).args(["config", "set", "--global", "id.width", "4"]).assert().success();
+    git_issue(&temp).args(["config", "set", "--global", "author.name", "Ada"]).assert().success();
+    git_issue(&temp).args(["config", "set", "labels.allowed", "[bug, feature]"]).assert().success();
+
+    git_issue(&temp).args(["create", "-t", "Wide", "-c", "body", "--label", "Bug"])
+        .assert().success().stdout(predicate::str::contains("0001 | Wide"));
+    git_issue(&temp).args(["create", "-p", "0001", "-t", "Child", "-c", "body"])
+        .assert().success().stdout(predicate::str::contains("0001-0001 | Child"));
+    git_issue(&temp).args(["create", "-t", "Bad", "-c", "body", "--label", "chore"])
+        .assert().failure().stderr(predicate::str::contains("label 'chore' is not allowed"));
+
+    git_issue(&temp).args(["config", "get", "--global", "id.width"])
+        .assert().success().stdout("4\n");
+    git_issue(&temp).args(["ls", "--format", "json"])
+        .assert().success().stdout(predicate::str::contains("\"id\": \"0001-0001\""));
+    git_issue(&temp).arg("log")
+        .assert().success().stdout(predicate::str::contains("CREATE id=0001 title=Wide author=Ada"));
+}
@@ -0,0 +1,124 @@
+// End-to-end tests for `git issue export csv` and `git issue import csv`
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_csv_export_columns() {
+    let temp = setup_temp_dir();
+    let plan = r#"{"title": "Epic", "content": "Plan", "labels": ["feature", "ui"],
+        "sub_issues": [{"title": "Say \"hi\", then", "content": "Body"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp).args(["comment", "001", "-m", "noted"]).assert().success();
+
+    git_issue(&temp).args(["export", "csv"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "id,parent,title,state,labels,comments\n001,,Epic,open,\"feature,ui\",1\n001-001,001,\"Say \"\"hi\"\", then\",open,,0\n",
+        ));
+    git_issue(&temp).args(["export", "csv", "--tsv", "--columns", "title,id"])
+        .assert().success()
+        .stdout(predicate::str::diff("title\tid\nEpic\t001\n\"Say \"\"hi\"\", then\"\t001-001\n"));
+    git_issue(&temp).args(["export", "csv", "--columns", "id,owner"])
+        .assert().failure()
+        .stderr(predicate::str::contains("unknown column 'owner'"));
+}
+
+#[test]
+fn test_csv_import_creates_and_updates() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).args(["create", "-t", "Existing", "-c", "body"]).assert().success();
+    fs::write(
+        temp.path().join("rows.csv"),
+        "id,parent,title,state,labels\n001,,Renamed,,bug\nepic,,Epic,,\n,epic,Child,closed,\n,001,Other child,,ui\n",
+    )
+    .unwrap();
+
+    git_issue(&temp).args(["import", "csv", "rows.csv", "--dry-run"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "would update 001\nwould create \"Epic\"\nwould create \"Child\" under epic -> closed\nwould create \"Other child\" under 001\n",
+        ));
+    git_issue(&temp).args(["ls", "--state", "all"])
+        .assert().success()
+        .stdout(predicate::str::contains("Epic").not());
+
+    git_issue(&temp).args(["import", "csv", "rows.csv"])
+        .assert().success()
+        .stdout(predicate::str::contains("imported 3 created, 1 updated"));
+    git_issue(&temp).args(["export", "csv", "--columns", "id,title,state,labels"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "id,title,state,labels\n001,Renamed,open,bug\n001-001,Other child,open,ui\n002,Epic,open,\n002-001,Child,closed,\n",
+        ));
+    git_issue(&temp).args(["log"])
+        .assert().success()
+        .stdout(predicate::str::contains("EDIT id=001 source=import"))
+        .stdout(predicate::str::contains("STATUS id=002-001 state=closed msg=imported"))
+        .stdout(predicate::str::contains("IMPORT format=csv file=rows.csv created=3 updated=1"));
+
+    // Re-importing an export changes nothing
+    git_issue(&temp).args(["export", "csv", "-o", "all.tsv", "--tsv"]).assert().success();
+    git_issue(&temp).args(["import", "csv", "all.tsv"])
+        .assert().success()
+        .stdout(predicate::str::diff("imported 0 created, 0 updated\n"));
+}
+
+#[test]
+fn test_csv_import_reports_row_errors() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).args(["create", "-t", "Existing", "-c", "body"]).assert().success();
+    fs::write(
+        temp.path().join("rows.csv"),
+        "id,parent,title,state,priority\n,,No state,shipped,\n,zzz,Orphan,,\n001,,,,\n,,Fine,,p9x\n,,Good,,p1\n",
+    )
+    .unwrap();
+
+    git_issue(&temp).args(["import", "csv", "rows.csv", "--dry-run"])
+        .assert().failure()
+        .stderr(predicate::str::contains("4 row(s) failed, nothing imported"))
+        .stderr(predicate::str::contains("row 2: unknown state 'shipped'"))
+        .stderr(predicate::str::contains("row 3: unknown parent 'zzz'"))
+        .stderr(predicate::str::contains("row 4: title cannot be empty"))
+        .stderr(predicate::str::contains("row 5: "));
+    git_issue(&temp).args(["import", "csv", "rows.csv"]).assert().failure();
+    git_issue(&temp).args(["ls"])
+        .assert().success()
+        .stdout(predicate::str::contains("Good").not());
+}
+
+#[test]
+fn test_csv_import_checks_state_rules_up_front() {
+    let temp = setup_temp_dir();
+    let plan = r#"{"title": "Epic", "content": "body", "sub_issues": [{"title": "A", "content": "body"}, {"title": "B", "content": "body"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Done", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Done child", "-c", "body", "-p", "002"]).assert().success();
+    for id in ["002-001", "002"] {
+        git_issue(&temp).args(["close", id, "-m", "done"]).assert().success();
+    }
+    fs::write(temp.path().join("rows.csv"), "id,state\n001-001,closed\n001,closed\n002-001,open\n").unwrap();
+
+    // The dry run refuses what the real run would
+    for extra in [&["--dry-run"][..], &[]] {
+        git_issue(&temp).args(["import", "csv", "rows.csv"]).args(extra)
+            .assert().failure()
+            .stderr(predicate::str::contains("2 row(s) failed, nothing imported"))
+            .stderr(predicate::str::contains("row 3: child issues are still pending"))
+            .stderr(predicate::str::contains("row 4: parent issue closed"));
+    }
+    git_issue(&temp).args(["ls"])
+        .assert().success()
+        .stdout(predicate::str::contains("001-001"));
+
+    // Closing every sub-issue in the same file lets the parent close too
+    fs::write(temp.path().join("rows.csv"), "id,parent,title,state\n001,,Epic,closed\n001-001,001,A,closed\n001-002,001,B,closed\nnew,001,Late,closed\n").unwrap();
+    git_issue(&temp).args(["import", "csv", "rows.csv"])
+        .assert().success()
+        .stdout(predicate::str::contains("imported 1 created, 0 updated"));
+    git_issue(&temp).args(["ls"]).assert().success().stdout(predicate::str::contains("001").not());
+}
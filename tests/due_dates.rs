@@ -0,0 +1,82 @@
+// End-to-end tests for due dates, agenda and calendar export
+
+use chrono::{Days, Local};
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_overdue_and_agenda() {
+    let temp = setup_temp_dir();
+    let today = Local::now().date_naive();
+    let yesterday = (today - Days::new(1)).to_string();
+    let soon = (today + Days::new(3)).to_string();
+    let later = (today + Days::new(30)).to_string();
+
+    git_issue(&temp).args(["create", "-t", "Late", "-c", "body", "--due", &yesterday]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Soon", "-c", "body", "--due", &soon]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Someday", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["edit", "003", "--due", &later, "--start", &soon]).assert().success();
+    git_issue(&temp).args(["edit", "003", "--start", "2999-01-01"])
+        .assert().failure().stderr(predicate::str::contains("is after due date"));
+    git_issue(&temp).args(["create", "-t", "Done", "-c", "body", "--due", &yesterday]).assert().success();
+    git_issue(&temp).args(["close", "004", "-m", "done"]).assert().success();
+
+    git_issue(&temp).args(["ls", "--overdue", "--state", "all"])
+        .assert().success()
+        .stdout(predicate::str::contains(format!("001 | Late [due {}]", yesterday)))
+        .stdout(predicate::str::contains("Soon").not())
+        .stdout(predicate::str::contains("Done").not());
+    git_issue(&temp).args(["ls", "--due-within", "7d"])
+        .assert().success()
+        .stdout(predicate::str::contains("Late"))
+        .stdout(predicate::str::contains("Soon"))
+        .stdout(predicate::str::contains("Someday").not());
+
+    git_issue(&temp).arg("agenda")
+        .assert().success()
+        .stdout(predicate::str::contains("overdue (1)\n  001 | Late"))
+        .stdout(predicate::str::contains("next 7 days (1)\n  002 | Soon"))
+        .stdout(predicate::str::contains("later (1)\n  003 | Someday"))
+        .stdout(predicate::str::contains("Done").not());
+
+    git_issue(&temp).args(["view", "003"])
+        .assert().success()
+        .stdout(predicate::str::contains(format!("starts {}", soon)));
+    git_issue(&temp).args(["edit", "003", "--due", "none", "--start", "none"]).assert().success();
+    git_issue(&temp).args(["ls", "--due-within", "60d"])
+        .assert().success()
+        .stdout(predicate::str::contains("Someday").not());
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("EDIT id=003 due=none start=none"));
+}
+
+#[test]
+fn test_plan_dates_and_ics_export() {
+    let temp = setup_temp_dir();
+    let plan = r#"{"title": "Launch", "content": "body", "due": "2026-03-31",
+        "sub_issues": [{"title": "Docs", "content": "Write, then review", "due": "2026-03-15"},
+                       {"title": "Undated", "content": "body"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    let bad = r#"{"title": "Bad", "content": "body", "due": "2026-03-01", "start": "2026-04-01", "sub_issues": []}"#;
+    git_issue(&temp).args(["plan", "--json", bad])
+        .assert().failure().stderr(predicate::str::contains("is after due date"));
+
+    git_issue(&temp).args(["export", "ics"])
+        .assert().success()
+        .stdout(predicate::str::starts_with("BEGIN:VCALENDAR\r\n"))
+        .stdout(predicate::str::contains("UID:001@git-issue\r\n"))
+        .stdout(predicate::str::contains("DTSTART;VALUE=DATE:20260315\r\n"))
+        .stdout(predicate::str::contains("DESCRIPTION:Write\\, then review\r\n"))
+        .stdout(predicate::str::contains("001-002").not());
+
+    let out = temp.path().join("issues.ics");
+    git_issue(&temp).args(["export", "ics", "--out", out.to_str().unwrap()]).assert().success();
+    let ics = fs::read_to_string(&out).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+}
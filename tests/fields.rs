@@ -0,0 +1,71 @@
+// End-to-end tests for custom typed fields
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+const CONFIG: &str = "fields:
+  severity:
+    type: enum
+    values: [low, medium, high]
+  customer:
+    type: string
+  seats:
+    type: int
+  design_due:
+    type: date
+  blocking:
+    type: bool
+";
+
+#[test]
+fn test_fields_are_typed_filtered_and_sorted() {
+    let temp = setup_temp_dir();
+    let issues_dir = temp.path().join(".issues");
+    fs::create_dir_all(&issues_dir).unwrap();
+    fs::write(issues_dir.join("config.yaml"), CONFIG).unwrap();
+
+    git_issue(&temp)
+        .args(["create", "-t", "Crash", "-c", "body", "--field", "severity=high", "--field", "customer=Acme"])
+        .assert().success();
+    git_issue(&temp).args(["create", "-t", "Typo", "-c", "body", "--field", "severity=low"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Plain", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Bad", "-c", "body", "--field", "severity=urgent"])
+        .assert().failure()
+        .stderr(predicate::str::contains("expected one of: low, medium, high"));
+    git_issue(&temp).args(["create", "-t", "Bad", "-c", "body", "--field", "seats=many"])
+        .assert().failure()
+        .stderr(predicate::str::contains("invalid value 'many' for int field 'seats'"));
+    git_issue(&temp).args(["create", "-t", "Bad", "-c", "body", "--field", "owner=me"])
+        .assert().failure()
+        .stderr(predicate::str::contains("unknown field 'owner'"));
+
+    git_issue(&temp).args(["edit", "003", "--field", "severity=medium", "--field", "blocking=yes"]).assert().success();
+    git_issue(&temp).args(["edit", "001", "--field", "customer="]).assert().success();
+
+    git_issue(&temp).args(["ls", "--sort", "severity"])
+        .assert().success()
+        .stdout(predicate::str::is_match("002 \\| Typo\n003 \\| Plain\n001 \\| Crash\n").unwrap());
+    git_issue(&temp).args(["ls", "--field", "blocking=true"])
+        .assert().success()
+        .stdout(predicate::str::contains("003 | Plain"))
+        .stdout(predicate::str::contains("Crash").not());
+    git_issue(&temp).args(["view", "003"])
+        .assert().success()
+        .stdout(predicate::str::contains("blocking: true\nseverity: medium"));
+    git_issue(&temp).args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains("customer").not());
+
+    let plan = r#"{"title": "Epic", "content": "body", "fields": {"seats": 40, "design_due": "2026-05-01"},
+        "sub_issues": [{"title": "Sub", "content": "body", "fields": {"severity": "low"}}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    let yaml = fs::read_to_string(issues_dir.join("004.yaml")).unwrap();
+    assert!(yaml.contains("extra:\n  design_due: 2026-05-01\n  seats: 40\n"));
+    let bad = r#"{"title": "Epic", "content": "body", "sub_issues": [{"title": "Sub", "content": "body", "fields": {"seats": "x"}}]}"#;
+    git_issue(&temp).args(["plan", "--json", bad]).assert().failure();
+    assert!(!issues_dir.join("005.yaml").exists());
+}
@@ -0,0 +1,169 @@
+// End-to-end tests for `git issue import github` and `git issue export github`
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+
+mod common;
+
+use common::setup_temp_dir;
+
+fn git_issue(temp: &tempfile::TempDir) -> Command {
+    let mut cmd = common::git_issue(temp);
+    cmd.env_remove("GITHUB_TOKEN");
+    cmd
+}
+
+/// Stand-in for the issues API: answers `count` requests with numbers from 100, reporting each one
+fn stand_in(count: usize) -> (String, mpsc::Receiver<(String, Option<String>, serde_json::Value)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}/repos/me/project", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for number in 100..100 + count {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let (mut length, mut auth) = (0, None);
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let Some((name, value)) = header.trim_end().split_once(": ") else { break };
+                match name.to_lowercase().as_str() {
+                    "content-length" => length = value.parse().unwrap(),
+                    "authorization" => auth = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            sender.send((request_line.trim_end().to_string(), auth, serde_json::from_slice(&body).unwrap())).unwrap();
+            let reply = format!(r#"{{"number": {0}, "html_url": "https://example.test/issues/{0}"}}"#, number);
+            write!(
+                &stream,
+                "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                reply.len(),
+                reply
+            )
+            .unwrap();
+        }
+    });
+    (base, receiver)
+}
+
+#[test]
+fn test_github_import() {
+    let temp = setup_temp_dir();
+    let export = r#"[
+        {"number": 12, "title": "Login page", "body": "Build it", "state": "OPEN", "labels": [{"name": "ui"}],
+         "comments": [{"author": {"login": "ann"}, "body": "On it", "createdAt": "2026-02-03T10:00:00Z"}]},
+        {"number": 10, "title": "Auth epic", "body": "Auth work\n\n- [x] #11\n- [ ] #12", "state": "OPEN",
+         "labels": [{"name": "feature"}]},
+        {"number": 11, "title": "Token store", "body": "", "state": "CLOSED", "stateReason": "COMPLETED"}
+    ]"#;
+    fs::write(temp.path().join("export.json"), export).unwrap();
+
+    git_issue(&temp).args(["import", "github", "export.json"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "001 | Auth epic - feature <- github#10\n001-001 | Token store <- github#11\n001-002 | Login page - ui <- github#12\nimported 3 created, 0 already imported\n",
+        ));
+    git_issue(&temp).args(["view", "001-002", "--raw"])
+        .assert().success()
+        .stdout(predicate::str::contains("imported from github#12\n\nBuild it\n"))
+        .stdout(predicate::str::contains("+++ @ann: On it"));
+    git_issue(&temp).args(["view", "001", "--raw"])
+        .assert().success()
+        .stdout(predicate::str::contains("Auth work\n\n@ref{001-001, 001-002}"));
+    git_issue(&temp).args(["ls", "--state", "all", "--reference", "github#11"])
+        .assert().success()
+        .stdout(predicate::str::contains("001-001").and(predicate::str::contains("Login").not()));
+    git_issue(&temp).args(["log"])
+        .assert().success()
+        .stdout(predicate::str::contains("CREATE id=001-002 title=Login page ref=github#12"))
+        .stdout(predicate::str::contains("IMPORT format=github file=export.json created=3 updated=0"));
+
+    // Importing again only picks up issues not seen before
+    fs::write(
+        temp.path().join("export.json"),
+        export.replace("\n    ]", r#", {"number": 13, "title": "Later", "body": "", "state": "OPEN"}]"#),
+    )
+    .unwrap();
+    git_issue(&temp).args(["import", "github", "export.json"])
+        .assert().success()
+        .stdout(predicate::str::diff("002 | Later <- github#13\nimported 1 created, 3 already imported\n"));
+}
+
+#[test]
+fn test_github_export_payloads() {
+    let temp = setup_temp_dir();
+    let plan = r#"{"title": "Epic", "content": "Plan body", "labels": ["feature"],
+        "sub_issues": [{"title": "API", "content": "Build it"}, {"title": "UI", "content": "Draw it"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp).args(["close", "001-002", "-m", "done"]).assert().success();
+
+    git_issue(&temp).args(["export", "github", "--out", "gh"])
+        .assert().success()
+        .stdout(predicate::str::diff("wrote 2 payloads to gh\n"));
+    let epic: serde_json::Value = serde_json::from_str(&fs::read_to_string(temp.path().join("gh/001.json")).unwrap()).unwrap();
+    assert_eq!(
+        epic,
+        serde_json::json!({
+            "title": "Epic",
+            "body": "Plan body\n\n### Sub-issues\n\n- [ ] API (001-001)\n- [x] UI (001-002)",
+            "labels": ["feature"]
+        })
+    );
+    let mapping = fs::read_to_string(temp.path().join("gh/mapping.json")).unwrap();
+    assert!(mapping.contains(r#""id": "001-001""#) && !mapping.contains("number"));
+    assert!(!temp.path().join("gh/001-002.json").exists());
+
+    git_issue(&temp).args(["export", "github", "--out", "gh", "--post"])
+        .assert().failure()
+        .stderr(predicate::str::contains("--post needs a URL"));
+}
+
+#[test]
+fn test_github_export_posts_to_configured_url() {
+    let temp = setup_temp_dir();
+    let plan = r#"{"title": "Epic", "content": "Plan body", "sub_issues": [{"title": "API", "content": "Build it"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    let (base, requests) = stand_in(2);
+    git_issue(&temp).args(["config", "set", "github.url", &base]).assert().success();
+
+    git_issue(&temp).args(["export", "github", "--out", "gh", "--post"])
+        .env("GITHUB_TOKEN", "secret")
+        .assert().success()
+        .stdout(predicate::str::diff("001-001 -> #100\n001 -> #101\nwrote 2 payloads to gh\n"));
+    let (line, auth, body) = requests.recv().unwrap();
+    assert_eq!(line, "POST /repos/me/project/issues HTTP/1.1");
+    assert_eq!(auth.as_deref(), Some("Bearer secret"));
+    assert_eq!(body["title"], "API");
+    let (_, _, body) = requests.recv().unwrap();
+    assert_eq!(body["body"], "Plan body\n\n### Sub-issues\n\n- [ ] #100");
+
+    let mapping: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(temp.path().join("gh/mapping.json")).unwrap()).unwrap();
+    assert_eq!(mapping[0]["id"], "001");
+    assert_eq!(mapping[0]["number"], 101);
+    assert_eq!(mapping[1]["url"], "https://example.test/issues/100");
+
+    // Published issues are not posted twice; the stand-in is gone, so any request would fail
+    git_issue(&temp).args(["export", "github", "--out", "gh", "--post"])
+        .assert().success()
+        .stdout(predicate::str::diff("wrote 2 payloads to gh\n"));
+
+    // Without --post the recorded numbers are still used and kept
+    git_issue(&temp).args(["export", "github", "--out", "gh"])
+        .assert().success()
+        .stdout(predicate::str::diff("wrote 2 payloads to gh\n"));
+    let epic: serde_json::Value = serde_json::from_str(&fs::read_to_string(temp.path().join("gh/001.json")).unwrap()).unwrap();
+    assert_eq!(epic["body"], "Plan body\n\n### Sub-issues\n\n- [ ] #100");
+    let again: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(temp.path().join("gh/mapping.json")).unwrap()).unwrap();
+    assert_eq!(again, mapping);
+}
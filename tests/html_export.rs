@@ -0,0 +1,46 @@
+// End-to-end tests for `git issue export html`
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_html_site_pages() {
+    let temp = setup_temp_dir();
+    let plan = r###"{"title": "Launch <v2>", "content": "## Goal\n- [x] design\n- [ ] ship", "labels": ["type::epic"],
+        "sub_issues": [{"title": "Docs", "content": "Write **docs**"}]}"###;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp).args(["milestone", "create", "v2"]).assert().success();
+    git_issue(&temp).args(["milestone", "set", "001", "v2"]).assert().success();
+    git_issue(&temp).args(["comment", "001-001", "-m", "drafted"]).assert().success();
+    git_issue(&temp).args(["close", "001-001", "-m", "done"]).assert().success();
+
+    git_issue(&temp).args(["export", "html", "--out", "site"])
+        .assert().success()
+        .stdout(predicate::str::contains("wrote 5 pages to site"));
+    let site = temp.path().join("site");
+    let read = |path: &str| fs::read_to_string(site.join(path)).unwrap();
+
+    let index = read("index.html");
+    assert!(index.contains("<a href=\"issues/001-001.html\">001-001</a>"));
+    assert!(index.contains("<select id=\"label\"><option value=\"\">any label</option><option>type::epic</option>"));
+    assert!(index.contains("Launch &lt;v2&gt;"));
+
+    let epic = read("issues/001.html");
+    assert!(epic.contains("<h2>Goal</h2>"));
+    assert!(epic.contains("<li><input type=\"checkbox\" disabled checked> design</li>"));
+    assert!(epic.contains("<h2>Sub-issues (1/1)</h2>"));
+    assert!(epic.contains("<a class=\"label\" href=\"../labels/type_3A_3Aepic.html\">type::epic</a>"));
+
+    let docs = read("issues/001-001.html");
+    assert!(docs.contains("<p>Write <strong>docs</strong></p>"));
+    assert!(docs.contains("milestone <a href=\"../milestones/v2.html\">v2</a>"));
+    assert!(predicate::str::is_match("<span class=\"kind\">comment</span><time>[0-9-]{10} [0-9:]{5} UTC</time><div>drafted</div>").unwrap().eval(&docs));
+    assert!(docs.contains("<span class=\"kind\">closed</span>"));
+
+    assert!(read("labels/type_3A_3Aepic.html").contains("001.html"));
+    assert!(read("milestones/v2.html").contains("1/2 closed"));
+}
@@ -0,0 +1,71 @@
+// End-to-end tests for multi-label filters in ls, search and export
+
+use predicates::prelude::*;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+fn seed(temp: &tempfile::TempDir) {
+    for (title, labels) in [
+        ("Login bug", "bug,backend,p1"),
+        ("Theme bug", "bug,frontend"),
+        ("Old bug", "bug,wontfix"),
+        ("Backend task", "backend,p1"),
+    ] {
+        git_issue(temp)
+            .args(["create", "-t", title, "-c", "body", "--label", labels, "--due", "2026-06-01"])
+            .assert().success();
+    }
+    git_issue(temp).args(["create", "-t", "Untagged bug", "-c", "body"]).assert().success();
+}
+
+#[test]
+fn test_ls_label_combinations() {
+    let temp = setup_temp_dir();
+    seed(&temp);
+
+    git_issue(&temp).args(["ls", "--label", "backend", "--label", "p1"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | Login bug"))
+        .stdout(predicate::str::contains("004 | Backend task"))
+        .stdout(predicate::str::contains("Theme").not());
+    git_issue(&temp).args(["ls", "--label", "bug", "--no-label", "wontfix"])
+        .assert().success()
+        .stdout(predicate::str::contains("Login bug"))
+        .stdout(predicate::str::contains("Theme bug"))
+        .stdout(predicate::str::contains("Old bug").not());
+    git_issue(&temp).args(["ls", "--any-label", "frontend", "--any-label", "wontfix"])
+        .assert().success()
+        .stdout(predicate::str::contains("Theme bug"))
+        .stdout(predicate::str::contains("Old bug"))
+        .stdout(predicate::str::contains("Login").not());
+    git_issue(&temp).args(["ls", "--unlabeled"])
+        .assert().success()
+        .stdout(predicate::str::diff("005 | Untagged bug\n"));
+    git_issue(&temp).args(["ls", "--unlabeled", "--label", "bug"]).assert().failure();
+}
+
+#[test]
+fn test_search_and_export_share_label_matching() {
+    let temp = setup_temp_dir();
+    seed(&temp);
+
+    git_issue(&temp).args(["search", "BUG", "--no-label", "wontfix"])
+        .assert().success()
+        .stdout(predicate::str::contains("Login bug"))
+        .stdout(predicate::str::contains("Untagged bug"))
+        .stdout(predicate::str::contains("Old bug").not());
+    git_issue(&temp).args(["search", "bug", "--unlabeled"])
+        .assert().success()
+        .stdout(predicate::str::diff("005 | Untagged bug\n"));
+    git_issue(&temp).args(["search", "nothing like this"])
+        .assert().success()
+        .stdout(predicate::str::contains("no matching issues"));
+
+    git_issue(&temp).args(["export", "ics", "--label", "backend"])
+        .assert().success()
+        .stdout(predicate::str::contains("UID:001@git-issue"))
+        .stdout(predicate::str::contains("UID:004@git-issue"))
+        .stdout(predicate::str::contains("UID:002@git-issue").not());
+}
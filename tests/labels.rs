@@ -0,0 +1,75 @@
+// End-to-end tests for the label registry, strict mode and scoped labels
+
+use predicates::prelude::*;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_label_registry_commands() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).args(["label", "create", "type::bug", "--color", "#d73a4a", "-d", "Something is broken"])
+        .assert().success();
+    git_issue(&temp).args(["label", "create", "type::bug"])
+        .assert().failure().stderr(predicate::str::contains("already exists"));
+    git_issue(&temp).args(["label", "create", "ui", "--color", "blue"])
+        .assert().failure().stderr(predicate::str::contains("expected #rrggbb"));
+
+    git_issue(&temp).args(["create", "-t", "A", "-c", "body", "--label", "type::bug,ui"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "B", "-c", "body", "--label", "gui,ui"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "C", "-c", "body", "--label", "type::bug,type::feature"])
+        .assert().failure().stderr(predicate::str::contains("share the scope 'type'"));
+
+    git_issue(&temp).args(["label", "ls"])
+        .assert().success()
+        .stdout(predicate::str::contains("type::bug (1) #d73a4a | Something is broken"))
+        .stdout(predicate::str::contains("ui (2) [unregistered]"));
+    git_issue(&temp).args(["ls", "--label", "type::*"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | A"))
+        .stdout(predicate::str::contains("002 | B").not());
+
+    git_issue(&temp).args(["label", "rename", "type::bug", "kind::bug"])
+        .assert().success().stdout(predicate::str::contains("1 issue(s) updated"));
+    git_issue(&temp).args(["label", "merge", "gui", "--into", "ui"]).assert().success();
+    git_issue(&temp).args(["view", "002"]).assert().success().stdout(predicate::str::contains("002 | B - ui\n"));
+    git_issue(&temp).args(["label", "delete", "ui"])
+        .assert().success().stdout(predicate::str::contains("ui | deleted from 2 issue(s)"));
+    git_issue(&temp).args(["view", "001"]).assert().success().stdout(predicate::str::contains("001 | A - kind::bug\n"));
+    git_issue(&temp).args(["label", "delete", "nope"])
+        .assert().failure().stderr(predicate::str::contains("unknown label 'nope'"));
+
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("LABEL rename from=type::bug to=kind::bug issues=1"))
+        .stdout(predicate::str::contains("LABEL merge from=gui into=ui issues=1"))
+        .stdout(predicate::str::contains("LABEL delete name=ui issues=2"));
+}
+
+#[test]
+fn test_strict_mode_rejects_unknown_labels() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).args(["config", "set", "labels.strict", "true"]).assert().success();
+    git_issue(&temp).args(["label", "create", "bug"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "A", "-c", "body", "--label", "BUG"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "B", "-c", "body", "--label", "feature"])
+        .assert().failure().stderr(predicate::str::contains("unknown label 'feature' (strict mode"));
+    let plan = r#"{"title": "Epic", "content": "body", "sub_issues": [{"title": "S", "content": "body", "labels": ["nope"]}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan])
+        .assert().failure().stderr(predicate::str::contains("unknown label 'nope'"));
+}
+
+#[test]
+fn test_edit_adds_and_removes_labels() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).args(["create", "-t", "A", "-c", "body", "--label", "bug,ui"]).assert().success();
+    git_issue(&temp).args(["edit", "001", "--add-label", "backend,BUG", "--remove-label", "UI"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | A - bug,backend"));
+    git_issue(&temp).args(["edit", "001", "--add-label", "type::bug,type::feature"])
+        .assert().failure().stderr(predicate::str::contains("share the scope 'type'"));
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("EDIT id=001 labels=bug,backend"));
+}
@@ -0,0 +1,92 @@
+// End-to-end tests for `git issue mcp`
+
+use serde_json::{json, Value};
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+/// Send JSON-RPC messages to a server session and collect its replies
+fn session(temp: &tempfile::TempDir, messages: &[Value]) -> Vec<Value> {
+    let input: String = messages.iter().map(|m| format!("{}\n", m)).collect();
+    let output = git_issue(temp).arg("mcp").write_stdin(input).assert().success().get_output().stdout.clone();
+    String::from_utf8(output).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect()
+}
+
+fn call(id: u64, name: &str, arguments: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call", "params": { "name": name, "arguments": arguments } })
+}
+
+#[test]
+fn test_mcp_handshake_and_tools() {
+    let temp = setup_temp_dir();
+    let replies = session(
+        &temp,
+        &[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize",
+                    "params": { "protocolVersion": "2025-06-18", "capabilities": {}, "clientInfo": { "name": "test" } } }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        ],
+    );
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["result"]["protocolVersion"], "2025-06-18");
+    assert_eq!(replies[0]["result"]["serverInfo"]["name"], "git-issue");
+    let names: Vec<&str> = replies[1]["result"]["tools"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert_eq!(
+        names,
+        [
+            "create", "plan", "ls", "view", "comment", "close", "reopen", "next", "search", "edit", "assign", "claim",
+            "release", "prioritize", "reorder", "check", "uncheck", "estimate", "track",
+        ]
+    );
+}
+
+#[test]
+fn test_mcp_tool_calls() {
+    let temp = setup_temp_dir();
+    let plan = json!({ "title": "Epic", "content": "Plan body", "auto_close": true, "sub_issues": [{ "title": "API", "content": "Build it" }] });
+    let replies = session(
+        &temp,
+        &[
+            call(1, "create", json!({ "title": "Bug", "content": "Broken", "label": ["bug"] })),
+            call(2, "plan", plan),
+            call(3, "comment", json!({ "id": "002-001", "message": "started" })),
+            call(4, "close", json!({ "id": "002-001", "message": "done" })),
+            call(5, "ls", json!({ "state": "all" })),
+            call(6, "view", json!({ "id": "002" })),
+            call(7, "next", json!({})),
+            call(8, "reopen", json!({ "id": "002", "message": "more" })),
+            call(9, "close", json!({ "id": "001" })),
+            call(10, "delete", json!({})),
+            json!("not a request"),
+        ],
+    );
+
+    let result = |n: usize| &replies[n]["result"];
+    assert_eq!(result(0)["structuredContent"]["id"], "001");
+    assert_eq!(result(0)["structuredContent"]["labels"], json!(["bug"]));
+    assert_eq!(result(0)["content"][0]["text"], "001 | Bug - bug\n");
+    assert_eq!(result(1)["structuredContent"], json!({ "id": "002", "title": "Epic", "sub_issues": ["002-001"] }));
+    assert_eq!(result(2)["structuredContent"]["comments"], json!(["+++ started"]));
+    // The parent opted into closing with its last sub-issue
+    assert_eq!(result(3)["structuredContent"]["state"], "closed");
+    assert_eq!(result(3)["structuredContent"]["closed_parents"], json!(["002"]));
+    let ids: Vec<&str> = result(4)["structuredContent"]["issues"].as_array().unwrap().iter().map(|i| i["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["001", "002", "002-001"]);
+    assert_eq!(result(5)["structuredContent"]["children"], json!(["002-001"]));
+    assert!(result(5)["content"][0]["text"].as_str().unwrap().contains("Plan body"));
+    assert_eq!(result(6)["structuredContent"]["issue"]["id"], "001");
+    assert_eq!(result(7)["structuredContent"]["state"], "open");
+    assert_eq!(result(8)["isError"], true);
+    assert!(result(8)["content"][0]["text"].as_str().unwrap().contains("invalid arguments: missing field `message`"));
+    assert_eq!(replies[9]["error"]["code"], -32602);
+    assert_eq!(replies[10]["error"]["code"], -32600);
+
+    let log = std::fs::read_to_string(temp.path().join(".issues/audit.log")).unwrap();
+    assert!(log.contains("CREATE id=001 title=Bug"));
+    assert!(log.contains("PLAN parent_id=002 parent_title=Epic"));
+    assert!(log.contains("COMMENT id=002-001 msg=started"));
+    assert!(log.contains("CLOSE id=002 msg=all sub-issues closed cascade=002-001"));
+    assert!(log.contains("REOPEN id=002 msg=more"));
+}
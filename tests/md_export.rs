@@ -0,0 +1,57 @@
+// End-to-end tests for `git issue export md`
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_markdown_export() {
+    let temp = setup_temp_dir();
+    let plan = r#"{"title": "Epic", "content": "Plan body", "labels": ["feature"],
+        "sub_issues": [{"title": "API", "content": "Build it"}, {"title": "UI", "content": "Draw it"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Unrelated", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["close", "001-001", "-m", "shipped"]).assert().success();
+
+    git_issue(&temp).args(["export", "md", "001"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "# 001 Epic
+
+- [ ] 001 Epic
+  - [x] 001-001 API
+  - [ ] 001-002 UI
+
+## 001 Epic
+
+**State:** open · **Labels:** feature
+
+Plan body
+
+### 001-001 API
+
+**State:** closed
+
+Build it
+
+### 001-002 UI
+
+**State:** open
+
+Draw it
+",
+        ));
+    git_issue(&temp).args(["export", "md", "--comments"])
+        .assert().success()
+        .stdout(predicate::str::contains("- [ ] 002 Unrelated\n"))
+        .stdout(predicate::str::is_match("\\*\\*Comments:\\*\\*\n\n- \\*closed\\* [0-9-]{10} [0-9:]{5} UTC: shipped\n").unwrap());
+
+    // Re-exports are byte-for-byte identical
+    git_issue(&temp).args(["export", "md", "--out", "a.md"]).assert().success();
+    git_issue(&temp).args(["export", "md", "--out", "b.md"]).assert().success();
+    assert_eq!(fs::read(temp.path().join("a.md")).unwrap(), fs::read(temp.path().join("b.md")).unwrap());
+    git_issue(&temp).args(["export", "md", "009"]).assert().failure();
+}
@@ -0,0 +1,76 @@
+// End-to-end tests for schema versioning and `migrate`
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::setup_temp_dir;
+
+const UNVERSIONED: &str = "id: '001'
+title: Legacy Issue
+content: Written before schema versioning
+labels:
+- bug
+state: Open
+comments:
+- +++ old comment
+";
+
+#[test]
+fn test_unversioned_issue_loads_and_migrates() {
+    let temp = setup_temp_dir();
+    let issues_dir = temp.path().join(".issues");
+    fs::create_dir_all(&issues_dir).unwrap();
+    fs::write(issues_dir.join("001.yaml"), UNVERSIONED).unwrap();
+
+    // Old files are upgraded in memory on load
+    let mut cmd = Command::cargo_bin("git-issue").unwrap();
+    cmd.current_dir(&temp).args(["view", "001"]);
+    cmd.assert().success()
+        .stdout(predicate::str::contains("Legacy Issue"))
+        .stdout(predicate::str::contains("+++ old comment"));
+
+    // Dry run prints a diff and leaves the file untouched
+    let mut cmd = Command::cargo_bin("git-issue").unwrap();
+    cmd.current_dir(&temp).args(["migrate", "--dry-run"]);
+    cmd.assert().success()
+        .stdout(predicate::str::contains("+schema_version: 2"))
+        .stdout(predicate::str::contains("-state: Open"))
+        .stdout(predicate::str::contains("+state: open"))
+        .stdout(predicate::str::contains("1 issue(s) would be migrated"));
+    assert_eq!(fs::read_to_string(issues_dir.join("001.yaml")).unwrap(), UNVERSIONED);
+
+    // Real run rewrites the store
+    let mut cmd = Command::cargo_bin("git-issue").unwrap();
+    cmd.current_dir(&temp).arg("migrate");
+    cmd.assert().success().stdout(predicate::str::contains("001 | migrated"));
+    let migrated = fs::read_to_string(issues_dir.join("001.yaml")).unwrap();
+    assert!(migrated.starts_with("schema_version: 2\n"));
+
+    // Second run has nothing left to do
+    let mut cmd = Command::cargo_bin("git-issue").unwrap();
+    cmd.current_dir(&temp).arg("migrate");
+    cmd.assert().success().stdout(predicate::str::contains("0 issue(s) migrated"));
+
+    let mut cmd = Command::cargo_bin("git-issue").unwrap();
+    cmd.current_dir(&temp).arg("log");
+    cmd.assert().success().stdout(predicate::str::contains("MIGRATE count=1"));
+}
+
+#[test]
+fn test_newer_schema_is_rejected() {
+    let temp = setup_temp_dir();
+    let issues_dir = temp.path().join(".issues");
+    fs::create_dir_all(&issues_dir).unwrap();
+    fs::write(
+        issues_dir.join("001.yaml"),
+        format!("schema_version: 99\n{}", UNVERSIONED),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("git-issue").unwrap();
+    cmd.current_dir(&temp).args(["view", "001"]);
+    cmd.assert().failure().stderr(predicate::str::contains("newer than the supported version"));
+}
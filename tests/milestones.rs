@@ -0,0 +1,54 @@
+// End-to-end tests for milestones
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_milestone_progress_includes_sub_issues() {
+    let temp = setup_temp_dir();
+    let issues_dir = temp.path().join(".issues");
+    fs::create_dir_all(&issues_dir).unwrap();
+    fs::write(
+        issues_dir.join("milestones.yaml"),
+        "- name: v1.2\n  description: Spring release\n  due: 2026-11-01\n",
+    )
+    .unwrap();
+
+    git_issue(&temp).args(["milestone", "create", "v2.0"]).assert().success();
+    git_issue(&temp).args(["milestone", "create", "v2.0"])
+        .assert().failure().stderr(predicate::str::contains("already exists"));
+    git_issue(&temp).args(["milestone", "create", "none"])
+        .assert().failure().stderr(predicate::str::contains("'none' is reserved"));
+
+    let plan = r#"{"title": "Epic", "content": "body", "milestone": "v1.2",
+        "sub_issues": [{"title": "A", "content": "body"}, {"title": "B", "content": "body"}]}"#;
+    git_issue(&temp).args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Later", "-c", "body", "--milestone", "v2.0"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Loose", "-c", "body", "--milestone", "v9"])
+        .assert().failure().stderr(predicate::str::contains("unknown milestone 'v9'"));
+    git_issue(&temp).args(["close", "001-001", "-m", "done"]).assert().success();
+
+    git_issue(&temp).args(["milestone", "ls"])
+        .assert().success()
+        .stdout("v1.2 | Spring release (due 2026-11-01) [1/3]\nv2.0 [0/1]\n");
+    git_issue(&temp).args(["milestone", "show", "v1.2"])
+        .assert().success()
+        .stdout(predicate::str::contains("open: 2, completed: 1"))
+        .stdout(predicate::str::contains("001-002 | B"));
+
+    // Sub-issues inherit the milestone for filtering
+    git_issue(&temp).args(["ls", "--milestone", "v1.2"])
+        .assert().success()
+        .stdout(predicate::str::contains("001-002 | B"))
+        .stdout(predicate::str::contains("Later").not());
+
+    git_issue(&temp).args(["milestone", "set", "002", "none"]).assert().success();
+    git_issue(&temp).args(["milestone", "ls"])
+        .assert().success().stdout(predicate::str::contains("v2.0 [0/0]"));
+    git_issue(&temp).arg("log")
+        .assert().success().stdout(predicate::str::contains("MILESTONE id=002 name=none"));
+}
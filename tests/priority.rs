@@ -0,0 +1,66 @@
+// End-to-end tests for priorities, ranks and `next` ordering
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+fn stdout(cmd: &mut Command) -> String {
+    String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_prioritize_and_sort() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+    for title in ["Low", "Urgent", "Unset"] {
+        git_issue(&temp).args(["create", "-t", title, "-c", "body"]).assert().success();
+    }
+
+    git_issue(&temp).args(["prioritize", "001", "3"])
+        .assert().success().stdout("001 | priority p3\n");
+    git_issue(&temp).args(["prioritize", "002", "P0"]).assert().success();
+    git_issue(&temp).args(["prioritize", "003", "urgent"])
+        .assert().failure().stderr(predicate::str::contains("invalid priority 'urgent'"));
+
+    let out = stdout(git_issue(&temp).args(["ls", "--sort", "priority"]));
+    assert_eq!(out, "002 | Urgent [p0]\n001 | Low [p3]\n003 | Unset\n");
+
+    git_issue(&temp).args(["prioritize", "002", "none"]).assert().success();
+    git_issue(&temp).args(["ls", "--sort", "size"])
+        .assert().failure().stderr(predicate::str::contains("unknown sort key 'size'"));
+}
+
+#[test]
+fn test_reorder_steers_next() {
+    let temp = setup_temp_dir();
+    fs::create_dir_all(temp.path().join(".issues")).unwrap();
+    git_issue(&temp).args(["create", "-t", "Epic", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Other", "-c", "body"]).assert().success();
+    for title in ["First", "Second", "Third"] {
+        git_issue(&temp).args(["create", "-p", "001", "-t", title, "-c", "body"]).assert().success();
+    }
+
+    git_issue(&temp).arg("next").assert().success().stdout(predicate::str::contains("001-001 | First"));
+
+    git_issue(&temp).args(["reorder", "001-003", "--before", "001-001"])
+        .assert().success().stdout("001-003, 001-001, 001-002\n");
+    git_issue(&temp).args(["reorder", "001-003", "--before", "002"])
+        .assert().failure().stderr(predicate::str::contains("are not siblings"));
+    git_issue(&temp).arg("next").assert().success().stdout(predicate::str::contains("001-003 | Third"));
+
+    let out = stdout(git_issue(&temp).args(["ls", "--sort", "rank"]));
+    assert!(out.find("001-003").unwrap() < out.find("001-001").unwrap());
+
+    // Priority outranks tree position
+    git_issue(&temp).args(["prioritize", "002", "p1"]).assert().success();
+    git_issue(&temp).arg("next").assert().success().stdout(predicate::str::contains("002 | Other [p1]"));
+
+    git_issue(&temp).arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("REORDER id=001-003 order=001-003,001-001,001-002"))
+        .stdout(predicate::str::contains("PRIORITIZE id=002 priority=p1"));
+}
@@ -0,0 +1,213 @@
+// End-to-end tests for `git issue serve`, against a server on localhost
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Stdio};
+
+mod common;
+
+use common::{git_issue, hold_store_lock, setup_temp_dir};
+
+/// A running server, stopped when dropped
+struct Server {
+    child: Child,
+    base: String,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn serve(temp: &tempfile::TempDir) -> Server {
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("git-issue"))
+        .args(["serve", "--port", "0"])
+        .current_dir(temp)
+        .env("XDG_CONFIG_HOME", temp.path().join("xdg"))
+        .env("GIT_ISSUE_AUTHOR", "alice")
+        .env("GIT_ISSUE_LOCK_TIMEOUT", "0.2")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+    let base = line.trim().strip_prefix("listening on ").expect("listening address").to_string();
+    Server { child, base }
+}
+
+/// Status and JSON reply, whether or not the request succeeded
+fn request(method: &str, url: &str, body: Option<Value>) -> (u16, Value) {
+    let request = ureq::request(method, url);
+    outcome(match body {
+        Some(body) => request.send_json(body),
+        None => request.call(),
+    })
+}
+
+fn outcome(result: Result<ureq::Response, ureq::Error>) -> (u16, Value) {
+    let response = match result {
+        Ok(response) => response,
+        Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => panic!("request failed: {}", e),
+    };
+    (response.status(), response.into_json().unwrap())
+}
+
+#[test]
+fn test_serve_commands() {
+    let temp = setup_temp_dir();
+    let server = serve(&temp);
+    let url = |path: &str| format!("{}{}", server.base, path);
+
+    let (status, issue) = request("POST", &url("/issues"), Some(json!({ "title": "Bug", "content": "Broken", "label": ["bug"] })));
+    assert_eq!(status, 201);
+    assert_eq!(issue["id"], "001");
+
+    let plan = json!({ "title": "Epic", "content": "Plan", "sub_issues": [{ "title": "API", "content": "Build it" }] });
+    let (status, created) = request("POST", &url("/plan"), Some(plan));
+    assert_eq!(status, 201);
+    assert_eq!(created, json!({ "id": "002", "title": "Epic", "sub_issues": ["002-001"] }));
+
+    let (status, issue) = request("POST", &url("/issues/002-001/comments"), Some(json!({ "message": "started" })));
+    assert_eq!(status, 200);
+    assert_eq!(issue["comments"], json!(["+++ started"]));
+
+    let (status, issue) = request("POST", &url("/issues/002-001/state"), Some(json!({ "state": "closed", "message": "done" })));
+    assert_eq!(status, 200);
+    assert_eq!(issue["state"], "closed");
+
+    let (_, listed) = request("GET", &url("/issues?label=bug"), None);
+    assert_eq!(listed["issues"].as_array().unwrap().len(), 1);
+    let (_, listed) = request("GET", &url("/issues?state=all&root=002"), None);
+    let ids: Vec<&str> = listed["issues"].as_array().unwrap().iter().map(|i| i["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["002", "002-001"]);
+
+    let (status, issue) = request("GET", &url("/issues/002"), None);
+    assert_eq!(status, 200);
+    assert_eq!(issue["children"], json!(["002-001"]));
+    let (_, next) = request("GET", &url("/next"), None);
+    assert_eq!(next["issue"]["id"], "001");
+
+    let (status, issue) = request("PATCH", &url("/issues/001"), Some(json!({ "title": "Crash", "add_label": ["ui"] })));
+    assert_eq!(status, 200);
+    assert_eq!((issue["title"].as_str(), issue["labels"].clone()), (Some("Crash"), json!(["bug", "ui"])));
+    let (_, found) = request("GET", &url("/search?query=broken&label=ui"), None);
+    assert_eq!(found["issues"][0]["id"], "001");
+    let (status, issue) = request("POST", &url("/issues/001/assignees"), Some(json!({ "who": ["bob"] })));
+    assert_eq!((status, issue["assignees"].clone()), (200, json!(["bob"])));
+    let (status, claimed) = request("POST", &url("/issues/001/claim"), Some(json!({ "lease": "1h" })));
+    assert_eq!((status, claimed["issue"]["id"].as_str()), (200, Some("001")));
+    let (_, claimed) = request("POST", &url("/claim"), Some(json!({})));
+    assert_eq!(claimed["issue"]["id"], "002");
+    let (status, issue) = request("POST", &url("/issues/001/release"), Some(json!({})));
+    assert_eq!((status, issue["claim"].clone()), (200, Value::Null));
+
+    let (_, issue) = request("POST", &url("/issues/001/priority"), Some(json!({ "priority": "p1" })));
+    assert_eq!(issue["priority"], 1);
+    let (_, issue) = request("POST", &url("/issues/001/estimate"), Some(json!({ "estimate": "2h" })));
+    assert_eq!(issue["estimate"], 2.0);
+    let (_, issue) = request("POST", &url("/issues/001/time"), Some(json!({ "spent": "30m" })));
+    assert_eq!(issue["rollup"]["spent_minutes"], 30);
+    let (_, order) = request("POST", &url("/issues/002/reorder"), Some(json!({ "before": "001" })));
+    assert_eq!(order["order"], json!(["002", "001"]));
+    request("POST", &url("/issues"), Some(json!({ "title": "List", "content": "- [ ] a\n- [ ] b" })));
+    let (_, issue) = request("POST", &url("/issues/003/check"), Some(json!({ "items": [1, 2] })));
+    assert_eq!(issue["content"], "- [x] a\n- [x] b");
+    let (_, issue) = request("POST", &url("/issues/003/uncheck"), Some(json!({ "items": [2] })));
+    assert_eq!(issue["content"], "- [x] a\n- [ ] b");
+
+    let (status, issue) = request("POST", &url("/issues/001/close"), Some(json!({ "message": "fixed" })));
+    assert_eq!((status, issue["state"].as_str()), (200, Some("closed")));
+    let (status, issue) = request("POST", &url("/issues/001/reopen"), Some(json!({ "message": "again" })));
+    assert_eq!((status, issue["state"].as_str()), (200, Some("open")));
+    request("POST", &url("/issues/002-001/reopen"), Some(json!({ "message": "more" })));
+    let (status, closed) = request("POST", &url("/issues/002/close"), Some(json!({ "message": "wrap up", "recursive": true })));
+    assert_eq!((status, closed["closed"].clone()), (200, json!(["002-001", "002"])));
+
+    // The CLI sees every change, and the audit log records them as the CLI would
+    git_issue(&temp).args(["view", "002-001", "--raw"])
+        .assert().success()
+        .stdout(predicates::str::contains("+++ started"));
+    let log = std::fs::read_to_string(temp.path().join(".issues/audit.log")).unwrap();
+    for entry in [
+        "CREATE id=001 title=Bug",
+        "PLAN parent_id=002 parent_title=Epic",
+        "COMMENT id=002-001 msg=started",
+        "STATUS id=002-001 state=closed msg=done",
+        "EDIT id=001 title=Crash",
+        "ASSIGN id=001 assignees=bob",
+        "CLAIM id=001 by=alice",
+        "RELEASE id=001 by=alice",
+        "PRIORITIZE id=001 priority=p1",
+        "ESTIMATE id=001 estimate=2",
+        "TRACK id=001 minutes=30 by=alice msg=",
+        "REORDER id=002 order=002,001",
+        "CHECK id=003 items=1,2 progress=2/2",
+        "UNCHECK id=003 items=2 progress=1/2",
+        "CLOSE id=001 msg=fixed",
+        "REOPEN id=001 msg=again",
+        "CLOSE id=002-001 msg=wrap up recursive=002",
+        "CLOSE id=002 msg=wrap up recursive=002",
+    ] {
+        assert!(log.contains(entry), "missing {}", entry);
+    }
+}
+
+#[test]
+fn test_serve_errors() {
+    let temp = setup_temp_dir();
+    let server = serve(&temp);
+    let url = |path: &str| format!("{}{}", server.base, path);
+
+    assert_eq!(request("GET", &url("/issues/009"), None).0, 404);
+    assert_eq!(request("GET", &url("/elsewhere"), None).0, 404);
+    assert_eq!(request("DELETE", &url("/issues"), None).0, 405);
+
+    let (status, reply) = request("POST", &url("/issues"), Some(json!({ "content": "no title" })));
+    assert_eq!(status, 422);
+    assert!(reply["error"].as_str().unwrap().contains("missing field `title`"));
+
+    request("POST", &url("/issues"), Some(json!({ "title": "Task", "content": "x" })));
+    let (status, reply) = request("POST", &url("/issues/001/close"), Some(json!({})));
+    assert_eq!(status, 422);
+    assert!(reply["error"].as_str().unwrap().contains("message"));
+
+    // Every ID in a request is checked, not only the one in the path
+    let (status, reply) = request("POST", &url("/issues"), Some(json!({ "title": "Sneaky", "content": "x", "parent": "../x" })));
+    assert_eq!(status, 422);
+    assert!(reply["error"].as_str().unwrap().contains("invalid issue ID '../x'"));
+    let bad = json!({ "message": "dup", "duplicate_of": "../../etc/passwd" });
+    let (status, reply) = request("POST", &url("/issues/001/close"), Some(bad));
+    assert_eq!(status, 422);
+    assert!(reply["error"].as_str().unwrap().contains("invalid issue ID '../../etc/passwd'"));
+    assert_eq!(request("GET", &url("/issues?root=..%2F..%2Fx"), None).0, 422);
+
+    // A held store lock makes writes wait, then fail, rather than race
+    let _held = hold_store_lock(&temp);
+    let (status, reply) = request("POST", &url("/issues/001/comments"), Some(json!({ "message": "late" })));
+    assert_eq!(status, 422);
+    assert!(reply["error"].as_str().unwrap().contains("timed out waiting"));
+    assert_eq!(request("GET", &url("/issues/001"), None).0, 200);
+}
+
+#[test]
+fn test_serve_refuses_cross_origin_requests() {
+    let temp = setup_temp_dir();
+    let server = serve(&temp);
+    let url = |path: &str| format!("{}{}", server.base, path);
+
+    // A form post or a DNS-rebound page cannot pass for a local client
+    let (status, reply) = outcome(ureq::post(&url("/issues")).set("Content-Type", "text/plain").send_string(r#"{"title": "x"}"#));
+    assert_eq!(status, 415);
+    assert!(reply["error"].as_str().unwrap().contains("application/json"));
+    assert_eq!(outcome(ureq::post(&url("/issues/001/release")).call()).0, 415);
+    let (status, reply) = outcome(ureq::get(&url("/issues")).set("Host", "attacker.example:7878").call());
+    assert_eq!(status, 403);
+    assert!(reply["error"].as_str().unwrap().contains("attacker.example"));
+    let port = server.base.rsplit_once(':').unwrap().1;
+    assert_eq!(outcome(ureq::get(&url("/issues")).set("Host", &format!("localhost:{}", port)).call()).0, 200);
+    assert!(!temp.path().join(".issues/001.yaml").exists());
+}
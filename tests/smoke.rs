@@ -1,5 +1,7 @@
 // End-to-end smoke tests for git-issue CLI
 
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use assert_cmd::Command;
 use predicates::prelude::*;
 use std::fs;
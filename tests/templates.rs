@@ -0,0 +1,77 @@
+// End-to-end tests for issue templates
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue_as, setup_temp_dir};
+
+fn git_issue(temp: &tempfile::TempDir) -> Command {
+    git_issue_as(temp, "alice")
+}
+
+fn write_templates(temp: &tempfile::TempDir) {
+    let dir = temp.path().join(".issues").join("templates");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("bug.md"),
+        "---\nlabels: [bug]\n---\n# {{title}}\n\nReported by {{author}}\n\n## Description\n\n## Acceptance Criteria\n",
+    )
+    .unwrap();
+    fs::write(dir.join("task.md"), "## Description\n\n## References\n").unwrap();
+}
+
+#[test]
+fn test_create_fills_and_validates_templates() {
+    let temp = setup_temp_dir();
+    write_templates(&temp);
+
+    // Explicit template renders placeholders when no content is given
+    git_issue(&temp).args(["create", "-t", "Crash on start", "--template", "bug"]).assert().success();
+    git_issue(&temp).args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains("# Crash on start\n\nReported by alice\n\n## Description"));
+
+    // Label default template checks supplied content
+    git_issue(&temp).args(["create", "-t", "Leak", "-c", "It leaks", "--label", "bug"])
+        .assert().failure()
+        .stderr(predicate::str::contains("missing section(s) required by template 'bug': Description, Acceptance Criteria"));
+    git_issue(&temp)
+        .args(["create", "-t", "Leak", "-c", "## Description\nIt leaks\n## Acceptance Criteria\n- fixed", "--label", "bug"])
+        .assert().success();
+    git_issue(&temp).args(["create", "-t", "Leak", "-c", "It leaks", "--label", "bug", "--template", "none"])
+        .assert().success();
+
+    git_issue(&temp).args(["create", "-t", "Nothing", "--template", "epic"])
+        .assert().failure()
+        .stderr(predicate::str::contains("unknown template 'epic' (templates: bug, task)"));
+    fs::write(temp.path().join(".issues").join("secret.md"), "## Secret\n").unwrap();
+    git_issue(&temp).args(["create", "-t", "Nothing", "--template", "../secret"])
+        .assert().failure()
+        .stderr(predicate::str::contains("invalid template name '../secret'"));
+    git_issue(&temp).args(["create", "-t", "Nothing"])
+        .assert().failure()
+        .stderr(predicate::str::contains("content is required"));
+}
+
+#[test]
+fn test_plan_validates_templates_up_front() {
+    let temp = setup_temp_dir();
+    write_templates(&temp);
+
+    let bad = r#"{"title": "Epic", "template": "task",
+        "sub_issues": [{"title": "Fix", "content": "no sections", "labels": ["bug"]}]}"#;
+    git_issue(&temp).args(["plan", "--json", bad])
+        .assert().failure()
+        .stderr(predicate::str::contains("issue 'Fix'"));
+    assert!(!temp.path().join(".issues").join("001.yaml").exists());
+
+    let good = r#"{"title": "Epic", "template": "task",
+        "sub_issues": [{"title": "Fix", "labels": ["bug"]}]}"#;
+    git_issue(&temp).args(["plan", "--json", good]).assert().success();
+    git_issue(&temp).args(["view", "001-001"])
+        .assert().success()
+        .stdout(predicate::str::contains("# Fix"));
+}
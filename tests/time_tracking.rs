@@ -0,0 +1,70 @@
+// End-to-end tests for estimates, time tracking and time reports
+
+use chrono::{Days, Local};
+use predicates::prelude::*;
+
+mod common;
+
+use common::{git_issue_as as git_issue, setup_temp_dir};
+
+#[test]
+fn test_estimates_and_tracked_time_roll_up() {
+    let temp = setup_temp_dir();
+    let plan = r#"{"title": "Epic", "content": "body", "labels": ["feature"], "estimate": 2,
+        "sub_issues": [{"title": "A", "content": "body", "labels": ["bug"], "estimate": "1h30m"},
+                       {"title": "B", "content": "body"}]}"#;
+    git_issue(&temp, "alice").args(["plan", "--json", plan]).assert().success();
+    git_issue(&temp, "alice").args(["estimate", "001-002", "4"]).assert().success()
+        .stdout(predicate::str::contains("001-002 | estimate 4h"));
+    git_issue(&temp, "alice").args(["estimate", "001-002", "soon"])
+        .assert().failure().stderr(predicate::str::contains("invalid estimate"));
+
+    git_issue(&temp, "alice").args(["track", "001-001", "1h", "-m", "repro"]).assert().success();
+    git_issue(&temp, "bob").args(["track", "001-001", "45m"]).assert().success()
+        .stdout(predicate::str::contains("001-001 | 45m by bob, 1h45m in total"));
+    git_issue(&temp, "bob").args(["track", "001", "30m"]).assert().success();
+    git_issue(&temp, "bob").args(["track", "001", "0m"])
+        .assert().failure().stderr(predicate::str::contains("at least one minute"));
+
+    git_issue(&temp, "alice").args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains(
+            "estimate 2h, spent 30m (with sub-issues: estimate 7.5h, spent 2h15m)",
+        ));
+    git_issue(&temp, "alice").args(["view", "001-001"])
+        .assert().success()
+        .stdout(predicate::str::contains("estimate 1.5h, spent 1h45m\n"));
+
+    git_issue(&temp, "alice").args(["assign", "001-001", "alice", "carol"]).assert().success();
+    git_issue(&temp, "alice").args(["report", "time"])
+        .assert().success()
+        .stdout(predicate::str::contains("time logged: 2h15m"))
+        .stdout(predicate::str::contains("  bug: 1h45m"))
+        .stdout(predicate::str::contains("  feature: 30m"))
+        .stdout(predicate::str::contains("  alice: 1h45m\n"))
+        .stdout(predicate::str::contains("  carol: 1h45m\n"))
+        .stdout(predicate::str::contains("  (unassigned): 30m\n"))
+        .stdout(predicate::str::contains("bob").not());
+    let tomorrow = (Local::now().date_naive() + Days::new(1)).to_string();
+    git_issue(&temp, "alice").args(["report", "time", "--since", &tomorrow])
+        .assert().success()
+        .stdout(predicate::str::contains(format!("time logged since {}: 0m", tomorrow)))
+        .stdout(predicate::str::contains("by label").not());
+
+    git_issue(&temp, "alice").arg("log")
+        .assert().success()
+        .stdout(predicate::str::contains("ESTIMATE id=001-002 estimate=4"))
+        .stdout(predicate::str::contains("TRACK id=001-001 minutes=60 by=alice msg=repro"));
+}
+
+#[test]
+fn test_point_estimates() {
+    let temp = setup_temp_dir();
+    git_issue(&temp, "alice").args(["config", "set", "estimate.unit", "points"]).assert().success();
+    git_issue(&temp, "alice").args(["create", "-t", "Story", "-c", "body", "--estimate", "3pt"]).assert().success();
+    git_issue(&temp, "alice").args(["create", "-t", "Story", "-c", "body", "--estimate", "2h"])
+        .assert().failure().stderr(predicate::str::contains("expected points"));
+    git_issue(&temp, "alice").args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains("estimate 3pt, spent 0m"));
+}
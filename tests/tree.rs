@@ -0,0 +1,85 @@
+// End-to-end tests for `ls --tree` and nested sub-issues
+
+use predicates::prelude::*;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+fn seed(temp: &tempfile::TempDir) {
+    let plan = r#"{"title": "Epic", "content": "body",
+        "sub_issues": [{"title": "A", "content": "body"}, {"title": "B", "content": "body"}, {"title": "C", "content": "body"}]}"#;
+    git_issue(temp).args(["plan", "--json", plan]).assert().success();
+    for (title, parent) in [("A1", "001-001"), ("A2", "001-001"), ("B1", "001-002")] {
+        git_issue(temp).args(["create", "-t", title, "-c", "body", "-p", parent]).assert().success();
+    }
+    git_issue(temp).args(["create", "-t", "Other", "-c", "body"]).assert().success();
+    for id in ["001-001-001", "001-001-002", "001-001"] {
+        git_issue(temp).args(["close", id, "-m", "done"]).assert().success();
+    }
+}
+
+#[test]
+fn test_grandchildren_get_distinct_ids() {
+    let temp = setup_temp_dir();
+    seed(&temp);
+    git_issue(&temp).args(["view", "001-001"])
+        .assert().success()
+        .stdout(predicate::str::contains("@ref{001-001-001, 001-001-002}"));
+    git_issue(&temp).args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains("@ref{001-001, 001-002, 001-003}"));
+}
+
+#[test]
+fn test_tree_rendering() {
+    let temp = setup_temp_dir();
+    seed(&temp);
+
+    git_issue(&temp).args(["ls", "--tree", "--state", "all"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "001 | Epic (3/6 sub-issues)
+├── 001-001 | A [closed] (2/2 sub-issues) (+2 hidden)
+├── 001-002 | B (0/1 sub-issues)
+│   └── 001-002-001 | B1
+└── 001-003 | C
+002 | Other
+",
+        ));
+    git_issue(&temp).args(["ls", "--tree", "--state", "all", "--root", "001-001"])
+        .assert().success()
+        .stdout(predicate::str::diff(
+            "001-001 | A [closed] (2/2 sub-issues)
+├── 001-001-001 | A1 [closed]
+└── 001-001-002 | A2 [closed]
+",
+        ));
+    git_issue(&temp).args(["ls", "--tree", "--depth", "1"])
+        .assert().success()
+        .stdout(predicate::str::diff("001 | Epic (3/6 sub-issues) (+3 hidden)\n002 | Other\n"));
+
+    // Ancestors of matches are kept so every branch stays rooted
+    git_issue(&temp).args(["ls", "--tree", "--root", "001-002"])
+        .assert().success()
+        .stdout(predicate::str::diff("001-002 | B (0/1 sub-issues)\n└── 001-002-001 | B1\n"));
+    git_issue(&temp).args(["ls", "--depth", "2"]).assert().failure();
+    git_issue(&temp).args(["ls", "--tree", "--format", "json"])
+        .assert().failure().stderr(predicate::str::contains("--tree only supports text output"));
+}
+
+#[test]
+fn test_parent_rules_apply_to_the_direct_parent() {
+    let temp = setup_temp_dir();
+    seed(&temp);
+
+    // 001-001 is closed, so its sub-issues stay closed even though the root is open
+    git_issue(&temp).args(["reopen", "001-001-001", "-m", "again"])
+        .assert().failure().stderr(predicate::str::contains("parent issue closed"));
+    git_issue(&temp).args(["close", "001-002", "-m", "done"])
+        .assert().failure().stderr(predicate::str::contains("child issues are still pending"));
+
+    git_issue(&temp).args(["close", "001-002-001", "-m", "done"]).assert().success();
+    git_issue(&temp).args(["close", "001-002", "-m", "done"]).assert().success();
+    git_issue(&temp).args(["reopen", "001-002", "-m", "again"]).assert().success();
+}
@@ -0,0 +1,15 @@
+// End-to-end tests for `git issue tui`
+
+use predicates::prelude::*;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_tui_requires_a_terminal() {
+    let temp = setup_temp_dir();
+    git_issue(&temp).arg("tui")
+        .assert().failure()
+        .stderr(predicate::str::contains("tui needs an interactive terminal"));
+}
@@ -0,0 +1,31 @@
+// End-to-end tests for rendered `view` output
+
+use predicates::prelude::*;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+#[test]
+fn test_view_renders_markdown_and_comment_kinds() {
+    let temp = setup_temp_dir();
+    let content = "## Steps\n- [ ] reproduce\n- [x] triage\n* see `login.rs`\n```\nlet a = 1;\n```";
+    git_issue(&temp).args(["create", "-t", "Login", "--content", content]).assert().success();
+    git_issue(&temp).args(["comment", "001", "-m", "looking"]).assert().success();
+    git_issue(&temp).args(["close", "001", "-m", "fixed"]).assert().success();
+
+    // Rendering is asked for since stdout is piped, and piped output is never colored
+    git_issue(&temp).args(["view", "001", "--render"])
+        .assert().success()
+        .stdout(predicate::str::contains("## Steps\n☐ reproduce\n☑ triage\n• see `login.rs`\n    let a = 1;\n"))
+        .stdout(predicate::str::is_match("comment · [0-9-]{10} [0-9:]{5}\n  looking\nclosed · .*\n  fixed\n$").unwrap())
+        .stdout(predicate::str::contains("\u{1b}[").not());
+    git_issue(&temp).args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains("- [ ] reproduce\n"))
+        .stdout(predicate::str::ends_with("+++ looking\n>>> fixed\n"));
+    git_issue(&temp).args(["view", "001", "--raw", "--render"]).assert().failure();
+    git_issue(&temp).args(["view", "001", "--format", "json"])
+        .assert().success()
+        .stdout(predicate::str::contains("\"comment_times\": ["));
+}
@@ -0,0 +1,95 @@
+// End-to-end tests for custom workflow states
+
+use predicates::prelude::*;
+use std::fs;
+
+mod common;
+
+use common::{git_issue, setup_temp_dir};
+
+const WORKFLOW: &str = "workflow:
+  states:
+  - name: todo
+  - name: in-progress
+  - name: review
+  - name: done
+    terminal: true
+  - name: wontfix
+    terminal: true
+  transitions:
+    todo: [in-progress, wontfix]
+    in-progress: [review, todo]
+    review: [done, in-progress]
+    done: [todo]
+    wontfix: [todo]
+";
+
+#[test]
+fn test_custom_workflow_states() {
+    let temp = setup_temp_dir();
+    let issues_dir = temp.path().join(".issues");
+    fs::create_dir_all(&issues_dir).unwrap();
+    fs::write(issues_dir.join("config.yaml"), WORKFLOW).unwrap();
+
+    git_issue(&temp).args(["create", "-t", "Epic", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["create", "-p", "001", "-t", "Task", "-c", "body"]).assert().success();
+    git_issue(&temp).args(["create", "-t", "Idea", "-c", "body"]).assert().success();
+
+    // Transitions outside the configured graph are rejected
+    git_issue(&temp).args(["status", "001-001", "review"])
+        .assert().failure().stderr(predicate::str::contains("transition from todo to review is not allowed"));
+    git_issue(&temp).args(["status", "001-001", "blocked"])
+        .assert().failure().stderr(predicate::str::contains("unknown state 'blocked'"));
+
+    git_issue(&temp).args(["status", "001-001", "in-progress", "-m", "started"])
+        .assert().success().stdout(predicate::str::contains("001-001 | todo -> in-progress"));
+    git_issue(&temp).args(["ls", "--state", "in-progress"])
+        .assert().success()
+        .stdout(predicate::str::contains("001-001 | Task [in-progress]"))
+        .stdout(predicate::str::contains("Idea").not());
+
+    // Any terminal state counts as closed for the pending-children rule
+    git_issue(&temp).args(["status", "001", "wontfix", "-m", "dropped"])
+        .assert().failure().stderr(predicate::str::contains("child issues are still pending"));
+
+    git_issue(&temp).args(["status", "001-001", "review"]).assert().success();
+    git_issue(&temp).args(["close", "001-001", "-m", "shipped"]).assert().success();
+    git_issue(&temp).args(["status", "001", "wontfix", "-m", "dropped"]).assert().success();
+
+    // `open`/`closed` filter by category
+    git_issue(&temp).arg("ls")
+        .assert().success()
+        .stdout(predicate::str::contains("002 | Idea\n"))
+        .stdout(predicate::str::contains("Epic").not());
+    git_issue(&temp).args(["ls", "--state", "closed"])
+        .assert().success()
+        .stdout(predicate::str::contains("001 | Epic [wontfix]"));
+
+    // Reopen maps onto the initial state
+    git_issue(&temp).args(["reopen", "001", "-m", "back"])
+        .assert().success();
+    git_issue(&temp).args(["view", "001"])
+        .assert().success()
+        .stdout(predicate::str::contains(">>> wontfix: dropped"))
+        .stdout(predicate::str::contains("<<< back"));
+
+    git_issue(&temp).arg("log")
+        .assert().success().stdout(predicate::str::contains("STATUS id=001-001 state=in-progress msg=started"));
+}
+
+#[test]
+fn test_unversioned_states_migrate_to_names() {
+    let temp = setup_temp_dir();
+    let issues_dir = temp.path().join(".issues");
+    fs::create_dir_all(&issues_dir).unwrap();
+    fs::write(
+        issues_dir.join("001.yaml"),
+        "id: '001'\ntitle: Old\ncontent: body\nlabels: []\nstate: Closed\ncomments: []\n",
+    )
+    .unwrap();
+
+    git_issue(&temp).args(["ls", "--state", "closed"])
+        .assert().success().stdout(predicate::str::contains("001 | Old [closed]"));
+    git_issue(&temp).args(["reopen", "001", "-m", "again"]).assert().success();
+    git_issue(&temp).arg("ls").assert().success().stdout(predicate::str::contains("001 | Old\n"));
+}